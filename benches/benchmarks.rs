@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion, black_box};
-use cache_lib::{Cache, LRU};
+use cache_lib::{Cache, Store, FIFO, LRU, RingFifo};
 use std::time::Duration;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -48,6 +48,38 @@ fn cache_get_benchmark(c: &mut Criterion) {
     });
 }
 
+fn cache_static_vs_boxed_benchmark(c: &mut Criterion) {
+    let mut boxed_cache = Cache::new(Box::new(LRU::new()), 10000);
+    let mut static_cache = Cache::with_policy(LRU::new(), 10000);
+
+    // Pre-fill both caches
+    for i in 0..10000 {
+        let key = TestKey { id: i };
+        let value = TestValue { data: format!("value{}", i) };
+        boxed_cache.set(key, value.clone());
+        static_cache.set(key, value);
+    }
+
+    let mut group = c.benchmark_group("lru_dispatch");
+    group.bench_function("boxed_get", |b| {
+        b.iter(|| {
+            for i in 0..10000 {
+                let key = TestKey { id: i };
+                black_box(boxed_cache.get(&key));
+            }
+        })
+    });
+    group.bench_function("static_get", |b| {
+        b.iter(|| {
+            for i in 0..10000 {
+                let key = TestKey { id: i };
+                black_box(static_cache.get(&key));
+            }
+        })
+    });
+    group.finish();
+}
+
 fn cache_remove_benchmark(c: &mut Criterion) {
     let eviction_policy = Box::new(LRU::new());
     let mut cache = Cache::new(eviction_policy, 10000);
@@ -69,9 +101,55 @@ fn cache_remove_benchmark(c: &mut Criterion) {
     });
 }
 
+fn store_presize_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("store_presize");
+    group.bench_function("default_insert_100k", |b| {
+        b.iter(|| {
+            let mut store = Store::new();
+            for i in 0..100_000 {
+                store.insert(TestKey { id: i }, i);
+            }
+            black_box(store)
+        })
+    });
+    group.bench_function("presized_insert_100k", |b| {
+        b.iter(|| {
+            let mut store = Store::with_capacity(100_000);
+            for i in 0..100_000 {
+                store.insert(TestKey { id: i }, i);
+            }
+            black_box(store)
+        })
+    });
+    group.finish();
+}
+
+fn fifo_vs_ring_fifo_benchmark(c: &mut Criterion) {
+    let mut fifo_cache = Cache::with_policy(FIFO::new(), 1000);
+    let mut ring_cache = Cache::with_policy(RingFifo::new(1000), 1000);
+
+    // Every set past the first thousand evicts the oldest key
+    let mut group = c.benchmark_group("fifo_queue");
+    group.bench_function("fifo_set", |b| {
+        b.iter(|| {
+            for i in 0..10000 {
+                fifo_cache.set(TestKey { id: i }, black_box(i));
+            }
+        })
+    });
+    group.bench_function("ring_fifo_set", |b| {
+        b.iter(|| {
+            for i in 0..10000 {
+                ring_cache.set(TestKey { id: i }, black_box(i));
+            }
+        })
+    });
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().warm_up_time(Duration::from_secs(10)).measurement_time(Duration::from_secs(20));
-    targets = cache_set_benchmark, cache_get_benchmark, cache_remove_benchmark
+    targets = cache_set_benchmark, cache_get_benchmark, cache_static_vs_boxed_benchmark, cache_remove_benchmark, store_presize_benchmark, fifo_vs_ring_fifo_benchmark
 }
 criterion_main!(benches);
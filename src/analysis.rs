@@ -0,0 +1,38 @@
+//!
+//! analysis.rs
+//!
+//! Helpers for comparing eviction policies on recorded access traces.
+//!
+
+use alloc::boxed::Box;
+use core::hash::Hash;
+use crate::cache::Cache;
+use crate::eviction::EvictionPolicy;
+use crate::stats::CacheStats;
+
+/// Replays an access trace through a fresh cache and reports how it performed.
+///
+/// Each `(key, value)` in the trace is looked up; on a miss the value is inserted, evicting
+/// according to `eviction_policy` as needed. Replaying the same trace with different policies
+/// gives a like-for-like comparison of their hit ratios.
+///
+/// # Parameters
+/// * `eviction_policy`: The eviction policy to evaluate.
+/// * `capacity`: The capacity of the cache to replay against.
+/// * `trace`: The sequence of accesses, with the value to insert on a miss.
+///
+/// # Returns
+/// The `CacheStats` collected over the replay.
+pub fn replay<K, V>(eviction_policy: Box<dyn EvictionPolicy<K>>, capacity: usize, trace: &[(K, V)]) -> CacheStats
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    let mut cache = Cache::new(eviction_policy, capacity);
+    for (key, value) in trace {
+        if cache.get(key).is_none() {
+            cache.set(key.clone(), value.clone());
+        }
+    }
+    *cache.stats()
+}
@@ -0,0 +1,201 @@
+//!
+//! array_cache.rs
+//!
+//! Provides a small LRU cache whose capacity is fixed at compile time and which never allocates.
+//!
+
+/// A stored entry along with the tick it was last used at.
+struct Slot<K, V> {
+    key: K,
+    value: V,
+    last_used: u64,
+}
+
+/// An LRU cache holding at most `N` entries in a fixed-size array.
+///
+/// Unlike `Cache`, an `ArrayCache` never touches the heap: its entries live inline, keys only
+/// need `Eq`, and lookups scan the array. This suits small caches on targets without an
+/// allocator, where `N` is a handful of entries and a linear scan is cheaper than hashing.
+/// When full, inserting a new key evicts the least recently used entry.
+///
+/// `N` must be greater than zero; a zero-capacity `ArrayCache` fails to compile.
+pub struct ArrayCache<K, V, const N: usize> {
+    slots: [Option<Slot<K, V>>; N],
+    len: usize,
+    tick: u64,
+}
+
+impl<K: Eq, V, const N: usize> ArrayCache<K, V, N> {
+    /// Creates a new empty `ArrayCache`.
+    ///
+    /// # Returns
+    /// A new `ArrayCache` with room for `N` entries.
+    pub fn new() -> Self {
+        const { assert!(N > 0, "ArrayCache capacity must be greater than zero") };
+        ArrayCache {
+            slots: core::array::from_fn(|_| None),
+            len: 0,
+            tick: 0,
+        }
+    }
+
+    /// Returns the maximum number of entries the cache can hold.
+    ///
+    /// # Returns
+    /// `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of entries in the cache.
+    ///
+    /// # Returns
+    /// The number of entries currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the cache is empty.
+    ///
+    /// # Returns
+    /// `true` if the cache holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Checks if the cache contains a key, without counting as a use.
+    ///
+    /// # Parameters
+    /// * `key`: The key to check.
+    ///
+    /// # Returns
+    /// `true` if the key is in the cache, otherwise `false`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Gets a value from the cache, marking it as the most recently used.
+    ///
+    /// # Parameters
+    /// * `key`: The key to look up.
+    ///
+    /// # Returns
+    /// A reference to the value if the key is in the cache, otherwise `None`.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = self.position(key)?;
+        let tick = self.next_tick();
+        let slot = self.slots[index].as_mut()?;
+        slot.last_used = tick;
+        Some(&slot.value)
+    }
+
+    /// Gets a value from the cache without changing its recency.
+    ///
+    /// # Parameters
+    /// * `key`: The key to look up.
+    ///
+    /// # Returns
+    /// A reference to the value if the key is in the cache, otherwise `None`.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let index = self.position(key)?;
+        self.slots[index].as_ref().map(|slot| &slot.value)
+    }
+
+    /// Inserts a key-value pair, evicting the least recently used entry if the cache is full.
+    ///
+    /// Setting a key that is already cached replaces its value and marks it as the most recently
+    /// used; nothing is evicted.
+    ///
+    /// # Parameters
+    /// * `key`: The key to insert.
+    /// * `value`: The value to insert.
+    ///
+    /// # Returns
+    /// The entry evicted to make room, if any.
+    pub fn set(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let tick = self.next_tick();
+        if let Some(index) = self.position(&key) {
+            if let Some(slot) = self.slots[index].as_mut() {
+                slot.value = value;
+                slot.last_used = tick;
+            }
+            return None;
+        }
+
+        let slot = Slot { key, value, last_used: tick };
+        if let Some(index) = self.slots.iter().position(Option::is_none) {
+            self.slots[index] = Some(slot);
+            self.len += 1;
+            return None;
+        }
+
+        // Full, so the new entry takes the least recently used one's place
+        let index = self.least_recently_used()?;
+        self.slots[index].replace(slot).map(|evicted| (evicted.key, evicted.value))
+    }
+
+    /// Removes a key from the cache.
+    ///
+    /// # Parameters
+    /// * `key`: The key to remove.
+    ///
+    /// # Returns
+    /// The removed value if the key was in the cache, otherwise `None`.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.position(key)?;
+        let slot = self.slots[index].take()?;
+        self.len -= 1;
+        Some(slot.value)
+    }
+
+    /// Removes every entry from the cache.
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the cached entries, in no particular order.
+    ///
+    /// # Returns
+    /// An iterator yielding a reference to each key and value.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().flatten().map(|slot| (&slot.key, &slot.value))
+    }
+
+    /// Finds the slot holding a key.
+    fn position(&self, key: &K) -> Option<usize> {
+        self.slots.iter().position(|slot| slot.as_ref().is_some_and(|slot| slot.key == *key))
+    }
+
+    /// Finds the occupied slot that was used longest ago.
+    fn least_recently_used(&self) -> Option<usize> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.as_ref().map(|slot| (index, slot.last_used)))
+            .min_by_key(|&(_, last_used)| last_used)
+            .map(|(index, _)| index)
+    }
+
+    /// Advances the use counter and returns its new value.
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+impl<K: Eq, V, const N: usize> Default for ArrayCache<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: core::fmt::Debug, V: core::fmt::Debug, const N: usize> core::fmt::Debug for ArrayCache<K, V, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(self.slots.iter().flatten().map(|slot| (&slot.key, &slot.value)))
+            .finish()
+    }
+}
@@ -0,0 +1,37 @@
+//!
+//! backing.rs
+//!
+//! Defines traits for connecting a cache to the backing store it sits in front of.
+//!
+
+/// Loads values from a backing store when the cache misses.
+pub trait Loader<K, V> {
+    /// Loads the value for a key.
+    ///
+    /// # Parameters
+    /// * `key`: The key that missed in the cache.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if the backing store has none either.
+    fn load(&self, key: &K) -> Option<V>;
+}
+
+/// Writes values back to a backing store.
+pub trait Writer<K, V> {
+    /// Writes a key-value pair to the backing store.
+    ///
+    /// # Parameters
+    /// * `key`: The key being written.
+    /// * `value`: The value associated with the key.
+    fn write(&self, key: &K, value: &V);
+}
+
+/// Controls when a cache passes its writes on to a `Writer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Every `set` is written immediately.
+    WriteThrough,
+    /// Writes are held in the cache and only written when the entry is evicted, expires or is
+    /// flushed.
+    WriteBack,
+}
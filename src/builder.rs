@@ -0,0 +1,262 @@
+//!
+//! builder.rs
+//!
+//! Provides a builder for configuring a Cache step by step.
+//!
+
+use alloc::boxed::Box;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::time::Duration;
+use crate::cache::{ Cache, GrowOnPressure, OnEvict, OnEvictOwned, OverflowPolicy };
+use crate::backing::{ Loader, Writer, WriteMode };
+use crate::clock::Clock;
+use crate::eviction::{ EvictionPolicy, LRU };
+use crate::observer::Observer;
+use crate::weigher::Weigher;
+
+/// The capacity used when none is given to the builder.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Builder for `Cache`, collecting optional settings before the cache is created.
+///
+/// Unset options fall back to an `LRU` eviction policy, a capacity of 1024 entries, no
+/// time-to-live, stats recording enabled and no observer, backing store or weigher.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use cache_lib::{ Cache, FIFO };
+///
+/// let mut cache: Cache<u64, String> = Cache::builder()
+///     .capacity(2)
+///     .eviction(Box::new(FIFO::new()))
+///     .default_ttl(Duration::from_secs(60))
+///     .with_stats(true)
+///     .build();
+///
+/// cache.set(1, "one".to_string());
+/// cache.set(2, "two".to_string());
+/// cache.set(3, "three".to_string());
+///
+/// assert_eq!(cache.get(&1), None);
+/// assert_eq!(cache.get(&3), Some(&"three".to_string()));
+/// assert_eq!(cache.stats().evictions, 1);
+/// ```
+pub struct CacheBuilder<K, V> {
+    capacity: usize,
+    eviction_policy: Option<Box<dyn EvictionPolicy<K>>>,
+    default_ttl: Option<Duration>,
+    clock: Option<Box<dyn Clock + Send + Sync>>,
+    stats_enabled: bool,
+    observer: Option<Box<dyn Observer<K> + Send + Sync>>,
+    on_evict: Option<OnEvict<K, V>>,
+    on_evict_owned: Option<OnEvictOwned<K, V>>,
+    loader: Option<Box<dyn Loader<K, V> + Send + Sync>>,
+    writer: Option<(Box<dyn Writer<K, V> + Send + Sync>, WriteMode)>,
+    weigher: Option<(Box<dyn Weigher<K, V> + Send + Sync>, usize)>,
+    overflow: OverflowPolicy,
+    growth: Option<GrowOnPressure>,
+    _values: PhantomData<V>,
+}
+
+impl<K, V> CacheBuilder<K, V> {
+    /// Creates a new `CacheBuilder` with the default configuration.
+    ///
+    /// # Returns
+    /// A `CacheBuilder` instance.
+    pub fn new() -> Self {
+        CacheBuilder {
+            capacity: DEFAULT_CAPACITY,
+            eviction_policy: None,
+            default_ttl: None,
+            clock: None,
+            stats_enabled: true,
+            observer: None,
+            on_evict: None,
+            on_evict_owned: None,
+            loader: None,
+            writer: None,
+            weigher: None,
+            overflow: OverflowPolicy::default(),
+            growth: None,
+            _values: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Parameters
+    /// * `capacity`: The cache capacity.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the eviction policy used when the cache is full.
+    ///
+    /// # Parameters
+    /// * `eviction_policy`: A boxed instance of a type implementing the `EvictionPolicy` trait.
+    pub fn eviction(mut self, eviction_policy: Box<dyn EvictionPolicy<K>>) -> Self {
+        self.eviction_policy = Some(eviction_policy);
+        self
+    }
+
+    /// Sets how long entries stay valid after they are inserted.
+    ///
+    /// # Parameters
+    /// * `ttl`: The time-to-live applied to every inserted entry.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the clock used to stamp and expire entries, such as a `MockClock` in tests.
+    ///
+    /// # Parameters
+    /// * `clock`: The time source.
+    pub fn clock(mut self, clock: Box<dyn Clock + Send + Sync>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Sets whether the cache records hits, misses and evictions.
+    ///
+    /// # Parameters
+    /// * `enabled`: `true` to record `CacheStats`, `false` to leave them at zero.
+    pub fn with_stats(mut self, enabled: bool) -> Self {
+        self.stats_enabled = enabled;
+        self
+    }
+
+    /// Sets an observer to be notified of hits, misses, inserts and evictions.
+    ///
+    /// # Parameters
+    /// * `observer`: The observer to notify.
+    pub fn observer(mut self, observer: Box<dyn Observer<K> + Send + Sync>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Sets a callback that borrows each evicted entry. See `Cache::set_on_evict`.
+    ///
+    /// # Parameters
+    /// * `on_evict`: Called with each evicted key and value.
+    pub fn on_evict(mut self, on_evict: OnEvict<K, V>) -> Self {
+        self.on_evict = Some(on_evict);
+        self
+    }
+
+    /// Sets a callback that takes ownership of evicted entries the cache would otherwise drop.
+    /// See `Cache::set_on_evict_owned`.
+    ///
+    /// # Parameters
+    /// * `on_evict_owned`: Called with each evicted key and value.
+    pub fn on_evict_owned(mut self, on_evict_owned: OnEvictOwned<K, V>) -> Self {
+        self.on_evict_owned = Some(on_evict_owned);
+        self
+    }
+
+    /// Sets a loader that misses fall through to.
+    ///
+    /// # Parameters
+    /// * `loader`: The loader for the backing store.
+    pub fn loader(mut self, loader: Box<dyn Loader<K, V> + Send + Sync>) -> Self {
+        self.loader = Some(loader);
+        self
+    }
+
+    /// Sets a writer that inserted values are passed on to.
+    ///
+    /// # Parameters
+    /// * `writer`: The writer for the backing store.
+    /// * `mode`: Whether values are written on every `set` or only when they leave the cache.
+    pub fn writer(mut self, writer: Box<dyn Writer<K, V> + Send + Sync>, mode: WriteMode) -> Self {
+        self.writer = Some((writer, mode));
+        self
+    }
+
+    /// Sets what `set` does when the cache is full but the eviction policy has nothing to evict.
+    ///
+    /// # Parameters
+    /// * `overflow`: The overflow behavior. Defaults to `OverflowPolicy::Grow`.
+    pub fn overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Lets the cache start small and raise its capacity while it misses too often.
+    ///
+    /// # Parameters
+    /// * `growth`: When and how far to grow. See `GrowOnPressure`.
+    pub fn grow_on_pressure(mut self, growth: GrowOnPressure) -> Self {
+        self.growth = Some(growth);
+        self
+    }
+
+    /// Bounds the cache by the total weight of its entries, such as their size in bytes, in
+    /// addition to the capacity.
+    ///
+    /// Entries are evicted until the total weight fits, and a value heavier than `max_weight` on
+    /// its own is not cached at all.
+    ///
+    /// # Parameters
+    /// * `weigher`: Weighs each entry as it is inserted.
+    /// * `max_weight`: The largest total weight the cache may hold.
+    pub fn weigher(mut self, weigher: Box<dyn Weigher<K, V> + Send + Sync>, max_weight: usize) -> Self {
+        self.weigher = Some((weigher, max_weight));
+        self
+    }
+}
+
+impl<K, V> CacheBuilder<K, V>
+where
+    K: Eq + Hash + Copy + Send + Sync + 'static,
+{
+    /// Creates the configured cache.
+    ///
+    /// # Returns
+    /// A `Cache` instance.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as `Cache::new`, or if growth on pressure is set with a
+    /// window of zero or for an eviction policy that manages its own capacity.
+    pub fn build(self) -> Cache<K, V> {
+        let eviction_policy = self.eviction_policy.unwrap_or_else(|| Box::new(LRU::new()));
+        let mut cache = Cache::new(eviction_policy, self.capacity);
+        cache.set_default_ttl(self.default_ttl);
+        if let Some(clock) = self.clock {
+            cache.set_clock(clock);
+        }
+        cache.set_stats_enabled(self.stats_enabled);
+        if let Some(observer) = self.observer {
+            cache.set_observer(observer);
+        }
+        if let Some(on_evict) = self.on_evict {
+            cache.set_on_evict(on_evict);
+        }
+        if let Some(on_evict_owned) = self.on_evict_owned {
+            cache.set_on_evict_owned(on_evict_owned);
+        }
+        if let Some(loader) = self.loader {
+            cache.set_loader(loader);
+        }
+        if let Some((writer, mode)) = self.writer {
+            cache.set_writer(writer, mode);
+        }
+        if let Some((weigher, max_weight)) = self.weigher {
+            cache.set_weigher(weigher, max_weight);
+        }
+        cache.set_overflow_policy(self.overflow);
+        if let Some(growth) = self.growth {
+            cache.set_grow_on_pressure(growth);
+        }
+        cache
+    }
+}
+
+impl<K, V> Default for CacheBuilder<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,22 +4,220 @@
 //! Defines the main Cache struct and provides primary caching functionality.
 //!
 
-use std::hash::Hash;
-use crate::eviction::EvictionPolicy;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+#[cfg(not(feature = "std"))]
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::fmt;
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::hash::Hash;
+use core::mem;
+use core::time::Duration;
+use crate::backing::{ Loader, Writer, WriteMode };
+use crate::builder::CacheBuilder;
+use crate::clock::{ Clock, DefaultClock, Instant };
+use crate::collections::{ hash_map, HashMap, HashSet };
+use crate::error::{ CacheError, InsertError };
+use crate::eviction::{ EvictionPolicy, PolicyMetadata, SLRU };
+use crate::observer::Observer;
+use crate::size::SizeOf;
+use crate::stats::CacheStats;
 use crate::store::Store;
+use crate::weigher::Weigher;
+
+/// The most entries `Cache`'s `Debug` output lists before eliding the rest.
+const DEBUG_ENTRIES: usize = 16;
+
+/// The most entries a new cache allocates room for up front, so a cache given a huge capacity as
+/// a practical "unbounded" doesn't allocate it all at once.
+const MAX_PRESIZE: usize = 1 << 20;
+
+/// A callback that borrows each evicted entry. See `Cache::set_on_evict`.
+pub type OnEvict<K, V> = Box<dyn FnMut(&K, &V) + Send + Sync>;
+
+/// A callback that takes ownership of evicted entries. See `Cache::set_on_evict_owned`.
+pub type OnEvictOwned<K, V> = Box<dyn FnMut(K, V) + Send + Sync>;
+
+/// The outcome of a `Cache::lookup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup<'a, V> {
+    /// The key holds a cached value.
+    Hit(&'a V),
+    /// The key was recorded as absent with `Cache::set_negative`.
+    Negative,
+    /// The cache knows nothing about the key.
+    Miss,
+}
+
+/// The outcome of a `Cache::set_reporting`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetOutcome<K, V> {
+    /// The key was new and there was room for it.
+    Inserted,
+    /// The key already held a value, which was replaced.
+    Updated,
+    /// Another entry was evicted to make room. If several were, this is the first of them.
+    Evicted {
+        /// The evicted key.
+        key: K,
+        /// The evicted value.
+        value: V,
+    },
+    /// The value wasn't cached, either because it weighed more than the cache's maximum weight,
+    /// in which case any value the key held before was removed, or because the cache was full and
+    /// its `OverflowPolicy` is `Reject`.
+    Rejected,
+}
+
+/// What `set` does when the cache is full but the eviction policy has no key to evict, such as a
+/// custom policy that pins every entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Insert the entry anyway, letting the cache grow past its capacity. This is the default, and
+    /// how every cache behaved before the overflow policy could be chosen.
+    #[default]
+    Grow,
+    /// Refuse the new entry, which `set_reporting` reports as `SetOutcome::Rejected`.
+    Reject,
+    /// Evict an arbitrary entry, bypassing the eviction policy, to make room.
+    EvictArbitrary,
+}
+
+/// Lets a cache raise its capacity while it misses too often, up to a ceiling.
+///
+/// Once the last `window` lookups are known, a full cache whose miss rate over them exceeds
+/// `miss_rate` multiplies its capacity by `factor`, growing by at least one entry and stopping at
+/// `max_capacity`. The window then starts over, so the larger cache is judged on its own lookups.
+/// Set it with `CacheBuilder::grow_on_pressure`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowOnPressure {
+    /// How many recent lookups the miss rate is measured over.
+    pub window: usize,
+    /// The miss rate, from 0 to 1, above which a full cache grows.
+    pub miss_rate: f64,
+    /// What the capacity is multiplied by each time the cache grows.
+    pub factor: f64,
+    /// The largest capacity the cache grows to.
+    pub max_capacity: usize,
+}
+
+/// A cached value together with the metadata needed to expire it.
+#[derive(Clone)]
+struct Entry<V> {
+    /// The cached value, or `None` for a negative entry recording that the key is absent.
+    value: Option<V>,
+    /// When the entry was stored, which `set` and other writes reset.
+    inserted_at: Instant,
+    expires_at: Option<Instant>,
+    /// Set when the value hasn't been written to the backing store yet.
+    dirty: bool,
+    /// The weight recorded by the cache's weigher at insertion, or zero without one.
+    weight: usize,
+    /// The cache's epoch when the entry was stored.
+    epoch: u64,
+}
+
+impl<V> Entry<V> {
+    /// Checks to see if the entry has outlived its time-to-live or was stored before the cache's
+    /// current epoch.
+    fn is_expired(&self, now: Instant, epoch: u64) -> bool {
+        self.epoch != epoch || self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// The accesses `get_shared` queues for the eviction policy, each with whether it was a hit.
+///
+/// With `std` the queue sits behind a `Mutex`, so shared lookups can run on several threads at
+/// once. Without it there is no lock to use, and the queue is a `RefCell`.
+struct PendingLookups<K> {
+    #[cfg(feature = "std")]
+    queue: std::sync::Mutex<Vec<(K, bool)>>,
+    #[cfg(not(feature = "std"))]
+    queue: RefCell<Vec<(K, bool)>>,
+}
+
+impl<K> PendingLookups<K> {
+    fn new() -> Self {
+        PendingLookups { queue: Default::default() }
+    }
+
+    /// Queues an access made through a shared reference.
+    fn push(&self, key: K, hit: bool) {
+        #[cfg(feature = "std")]
+        let mut queue = self.queue.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        #[cfg(not(feature = "std"))]
+        let mut queue = self.queue.borrow_mut();
+        queue.push((key, hit));
+    }
+
+    /// Returns the queue for a caller that has exclusive access anyway.
+    fn get_mut(&mut self) -> &mut Vec<(K, bool)> {
+        #[cfg(feature = "std")]
+        return self.queue.get_mut().unwrap_or_else(std::sync::PoisonError::into_inner);
+        #[cfg(not(feature = "std"))]
+        return self.queue.get_mut();
+    }
+}
+
+impl<K: Clone> Clone for PendingLookups<K> {
+    fn clone(&self) -> Self {
+        #[cfg(feature = "std")]
+        let queue = self.queue.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone();
+        #[cfg(not(feature = "std"))]
+        let queue = self.queue.borrow().clone();
+        PendingLookups { queue: queue.into() }
+    }
+}
 
 /// The Cache struct, providing the primary caching functionality.
 ///
+/// A cache is `Send` and `Sync` whenever its keys and values are, since eviction policies and
+/// every installed hook must be too. Shared references only read, so they can be used from
+/// several threads at once, such as through `view` or `get_shared`; anything that changes the
+/// cache needs exclusive access, so share a cache that is also written behind a `Mutex`. Without
+/// the `std` feature `get_shared` queues accesses through a `RefCell`, and the cache isn't `Sync`.
+///
 /// # Type Parameters
 /// * `K`: The type of the keys in the cache. Must implement `Eq`, `Hash`, and `Clone`.
 /// * `V`: The type of the values in the cache.
-pub struct Cache<K, V>
+/// * `P`: The eviction policy. Defaults to a boxed trait object so the policy can be chosen at
+///   runtime; naming a concrete policy type instead dispatches its calls statically.
+pub struct Cache<K, V, P = Box<dyn EvictionPolicy<K>>>
 where
     K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
 {
-    store: Store<K, V>,
-    eviction_policy: Box<dyn EvictionPolicy<K>>,
+    store: Store<K, Entry<V>>,
+    eviction_policy: P,
     capacity: usize,
+    default_ttl: Option<Duration>,
+    clock: Box<dyn Clock + Send + Sync>,
+    stats: CacheStats,
+    stats_enabled: bool,
+    pending_lookups: PendingLookups<K>,
+    observer: Option<Box<dyn Observer<K> + Send + Sync>>,
+    on_evict: Option<OnEvict<K, V>>,
+    on_evict_owned: Option<OnEvictOwned<K, V>>,
+    loader: Option<Box<dyn Loader<K, V> + Send + Sync>>,
+    writer: Option<(Box<dyn Writer<K, V> + Send + Sync>, WriteMode)>,
+    /// Shared rather than boxed, since weighing only reads, so clones can keep the weight bound.
+    weigher: Option<(Arc<dyn Weigher<K, V> + Send + Sync>, usize)>,
+    weight: usize,
+    overflow: OverflowPolicy,
+    /// The growth settings and the outcomes of recent lookups, `true` for a hit.
+    growth: Option<(GrowOnPressure, VecDeque<bool>)>,
+    /// The keys that eviction passes over.
+    pinned: HashSet<K>,
+    /// Whether lookups report accesses to the eviction policy.
+    tracking: bool,
+    /// Entries stored in an earlier epoch count as expired.
+    epoch: u64,
 }
 
 impl<K, V> Cache<K, V>
@@ -34,55 +232,1945 @@ where
     ///
     /// # Returns
     /// A `Cache` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero, or if the eviction policy manages its own capacity (see
+    /// `EvictionPolicy::capacity`) and it does not match `capacity`.
     pub fn new(eviction_policy: Box<dyn EvictionPolicy<K>>, capacity: usize) -> Self {
-        Cache {
-            store: Store::new(),
+        Cache::with_policy(eviction_policy, capacity)
+    }
+
+    /// Creates a new Cache instance, rejecting an invalid configuration instead of panicking.
+    ///
+    /// # Parameters
+    /// * `eviction_policy`: A boxed instance of a type implementing the `EvictionPolicy` trait.
+    /// * `capacity`: The maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Returns
+    /// A `Cache` instance, or `CacheError::ZeroCapacity` or `CacheError::CapacityMismatch` under
+    /// the conditions where `new` panics.
+    pub fn try_new(eviction_policy: Box<dyn EvictionPolicy<K>>, capacity: usize) -> Result<Self, CacheError> {
+        Cache::try_with_policy(eviction_policy, capacity)
+    }
+
+    /// Creates a new Cache that adopts the entries of an existing store.
+    ///
+    /// The store's keys are replayed through the eviction policy in arbitrary order, as with
+    /// `prime`. If the store holds more than `capacity` entries, the surplus is dropped.
+    ///
+    /// # Parameters
+    /// * `store`: The entries to adopt, such as a deserialized snapshot.
+    /// * `eviction_policy`: A boxed instance of a type implementing the `EvictionPolicy` trait.
+    /// * `capacity`: The maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Returns
+    /// A `Cache` instance.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as `new`.
+    pub fn from_store(store: Store<K, V>, eviction_policy: Box<dyn EvictionPolicy<K>>, capacity: usize) -> Self {
+        let mut cache = Cache::new(eviction_policy, capacity);
+        cache.prime(store.entries);
+        cache
+    }
+
+    /// Creates a new Cache that resists scans, using an SLRU sized to match `capacity`.
+    ///
+    /// About 20% of the capacity goes to the probationary segment and the rest to the protected
+    /// one, so keys that are read more than once survive a burst of keys that are read only once.
+    ///
+    /// # Parameters
+    /// * `capacity`: The maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Returns
+    /// A `Cache` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is less than 2, since each segment needs room for at least one entry.
+    pub fn scan_resistant(capacity: usize) -> Self
+    where
+        K: Copy + Send + Sync + 'static,
+    {
+        let probationary_capacity = (capacity / 5).max(1);
+        let protected_capacity = capacity.saturating_sub(probationary_capacity);
+        let eviction_policy = SLRU::new(probationary_capacity, protected_capacity);
+        Cache::new(Box::new(eviction_policy), capacity)
+    }
+
+    /// Creates a `CacheBuilder` for configuring a cache step by step.
+    ///
+    /// # Returns
+    /// A `CacheBuilder` with the default configuration.
+    pub fn builder() -> CacheBuilder<K, V> {
+        CacheBuilder::new()
+    }
+
+    /// Replaces the eviction policy on a live cache.
+    ///
+    /// Every cached key is replayed through the new policy's `on_insert` so it tracks the same
+    /// keys, in arbitrary order. Any recency or frequency history the old policy built up is
+    /// lost.
+    ///
+    /// # Parameters
+    /// * `eviction_policy`: The policy to use from now on.
+    ///
+    /// # Panics
+    /// Panics if the eviction policy manages its own capacity (see `EvictionPolicy::capacity`)
+    /// and it does not match the cache's capacity.
+    pub fn set_eviction_policy(&mut self, eviction_policy: Box<dyn EvictionPolicy<K>>) {
+        if let Some(policy_capacity) = eviction_policy.capacity() {
+            assert_eq!(
+                policy_capacity, self.capacity,
+                "eviction policy capacity ({}) does not match cache capacity ({})",
+                policy_capacity, self.capacity
+            );
+        }
+        self.apply_pending_accesses();
+        self.eviction_policy = eviction_policy;
+        let keys: Vec<K> = self.store.keys().cloned().collect();
+        for key in keys {
+            if let Some(dropped_key) = self.eviction_policy.on_insert(&key) {
+                self.take_entry(&dropped_key);
+            }
+        }
+    }
+}
+
+impl<K, V, P> Cache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Creates a new Cache instance that owns a concrete eviction policy.
+    ///
+    /// Unlike `new`, the policy isn't boxed, so its methods are dispatched statically and the
+    /// policy lives inline in the cache.
+    ///
+    /// # Parameters
+    /// * `eviction_policy`: An instance of a type implementing the `EvictionPolicy` trait.
+    /// * `capacity`: The maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Returns
+    /// A `Cache` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero, or if the eviction policy manages its own capacity (see
+    /// `EvictionPolicy::capacity`) and it does not match `capacity`.
+    pub fn with_policy(eviction_policy: P, capacity: usize) -> Self {
+        Cache::try_with_policy(eviction_policy, capacity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new Cache instance that owns a concrete eviction policy, rejecting an invalid
+    /// configuration instead of panicking.
+    ///
+    /// The store and the policy are allocated with room for `capacity` entries, up to about a
+    /// million, so the cache doesn't rehash while it fills.
+    ///
+    /// # Parameters
+    /// * `eviction_policy`: An instance of a type implementing the `EvictionPolicy` trait.
+    /// * `capacity`: The maximum number of items the cache can hold before evicting items.
+    ///
+    /// # Returns
+    /// A `Cache` instance, or `CacheError::ZeroCapacity` or `CacheError::CapacityMismatch` under
+    /// the conditions where `with_policy` panics.
+    pub fn try_with_policy(mut eviction_policy: P, capacity: usize) -> Result<Self, CacheError> {
+        if capacity == 0 {
+            return Err(CacheError::ZeroCapacity);
+        }
+        if let Some(policy_capacity) = eviction_policy.capacity() {
+            if policy_capacity != capacity {
+                return Err(CacheError::CapacityMismatch { policy_capacity, cache_capacity: capacity });
+            }
+        }
+        // Allocate up front so filling the cache never pauses to rehash
+        let presize = capacity.min(MAX_PRESIZE);
+        eviction_policy.reserve(presize);
+        Ok(Cache {
+            store: Store::with_capacity(presize),
             eviction_policy,
             capacity,
-        }
+            default_ttl: None,
+            clock: Box::new(DefaultClock),
+            stats: CacheStats::default(),
+            stats_enabled: true,
+            pending_lookups: PendingLookups::new(),
+            observer: None,
+            on_evict: None,
+            on_evict_owned: None,
+            loader: None,
+            writer: None,
+            weigher: None,
+            weight: 0,
+            overflow: OverflowPolicy::default(),
+            growth: None,
+            pinned: HashSet::new(),
+            tracking: true,
+            epoch: 0,
+        })
+    }
+
+    /// Sets the time-to-live applied to entries inserted from now on.
+    ///
+    /// # Parameters
+    /// * `default_ttl`: How long entries stay valid after insertion, or `None` to never expire.
+    pub(crate) fn set_default_ttl(&mut self, default_ttl: Option<Duration>) {
+        self.default_ttl = default_ttl;
+    }
+
+    /// Sets the clock used to stamp and expire entries.
+    ///
+    /// # Parameters
+    /// * `clock`: The time source.
+    pub(crate) fn set_clock(&mut self, clock: Box<dyn Clock + Send + Sync>) {
+        self.clock = clock;
+    }
+
+    /// Bounds the cache by the total weight of its entries as well as their number.
+    ///
+    /// # Parameters
+    /// * `weigher`: Weighs each entry as it is inserted.
+    /// * `max_weight`: The largest total weight the cache may hold.
+    pub(crate) fn set_weigher(&mut self, weigher: Box<dyn Weigher<K, V> + Send + Sync>, max_weight: usize) {
+        self.weigher = Some((Arc::from(weigher), max_weight));
+    }
+
+    /// Enables or disables recording of `CacheStats`.
+    ///
+    /// # Parameters
+    /// * `enabled`: Whether hits, misses and evictions should be counted.
+    pub(crate) fn set_stats_enabled(&mut self, enabled: bool) {
+        self.stats_enabled = enabled;
+    }
+
+    /// Installs an observer that is notified of hits, misses, inserts and evictions.
+    ///
+    /// # Parameters
+    /// * `observer`: The observer to notify, replacing any previous one.
+    pub fn set_observer(&mut self, observer: Box<dyn Observer<K> + Send + Sync>) {
+        self.observer = Some(observer);
+    }
+
+    /// Installs a callback that is shown each entry evicted to make room for another.
+    ///
+    /// The callback borrows the value just before the cache moves it on, so even a large value
+    /// needn't be cloned to be inspected. The value then goes wherever it would have gone anyway:
+    /// back to the caller of methods that return evicted entries, to the `on_evict_owned`
+    /// callback, or dropped. Negative entries have no value and aren't reported.
+    ///
+    /// # Parameters
+    /// * `on_evict`: Called with each evicted key and value, replacing any previous callback.
+    pub fn set_on_evict(&mut self, on_evict: OnEvict<K, V>) {
+        self.on_evict = Some(on_evict);
+    }
+
+    /// Installs a callback that takes ownership of evicted entries the cache would otherwise drop.
+    ///
+    /// Entries evicted by `set`, `set_negative` or a loader filling a miss are moved into the
+    /// callback, as are any beyond the first evicted by `set_reporting` or `try_set`. Methods
+    /// such as `resize` and `insert_all` return the evicted entries instead, so the caller owns
+    /// them already and the callback never sees them. The callback runs after `on_evict`.
+    ///
+    /// # Parameters
+    /// * `on_evict_owned`: Called with each evicted key and value, replacing any previous callback.
+    pub fn set_on_evict_owned(&mut self, on_evict_owned: OnEvictOwned<K, V>) {
+        self.on_evict_owned = Some(on_evict_owned);
+    }
+
+    /// Installs a loader that `get` falls through to on a miss, caching whatever it returns.
+    ///
+    /// # Parameters
+    /// * `loader`: The loader for the backing store, replacing any previous one.
+    pub fn set_loader(&mut self, loader: Box<dyn Loader<K, V> + Send + Sync>) {
+        self.loader = Some(loader);
+    }
+
+    /// Sets what `set` does when the cache is full and the eviction policy has nothing to evict.
+    ///
+    /// # Parameters
+    /// * `overflow`: The behavior to use from now on.
+    pub(crate) fn set_overflow_policy(&mut self, overflow: OverflowPolicy) {
+        self.overflow = overflow;
+    }
+
+    /// Lets the cache raise its capacity while it misses too often.
+    ///
+    /// # Parameters
+    /// * `growth`: When and how far to grow.
+    ///
+    /// # Panics
+    /// Panics if `growth.window` is zero, or if the eviction policy manages its own capacity (see
+    /// `EvictionPolicy::capacity`), since the policy's segments can't grow along with the cache.
+    pub(crate) fn set_grow_on_pressure(&mut self, growth: GrowOnPressure) {
+        assert!(growth.window > 0, "growth window must be at least 1");
+        assert!(
+            self.eviction_policy.capacity().is_none(),
+            "cannot grow a cache whose eviction policy manages its own capacity"
+        );
+        self.growth = Some((growth, VecDeque::with_capacity(growth.window)));
+    }
+
+    /// Installs a writer that inserted values are passed on to.
+    ///
+    /// # Parameters
+    /// * `writer`: The writer for the backing store, replacing any previous one.
+    /// * `mode`: Whether values are written on every `set` or only when they leave the cache.
+    pub fn set_writer(&mut self, writer: Box<dyn Writer<K, V> + Send + Sync>, mode: WriteMode) {
+        self.writer = Some((writer, mode));
     }
 
     /// Inserts a key-value pair into the store.
-    /// If the key already exists, it updates the value.
+    /// If the key already exists, it updates the value without evicting anything.
     ///
     /// # Parameters
     /// * `key`: The key to be inserted or updated.
     /// * `value`: The value associated with the key.
     pub fn set(&mut self, key: K, value: V) {
-        if self.store.entries.len() >= self.capacity {
-            if let Some(evicted_key) = self.eviction_policy.evict() {
-                self.store.remove(&evicted_key);
+        if let SetOutcome::Evicted { key, value } = self.set_reporting(key, value) {
+            self.release([(key, value)]);
+        }
+    }
+
+    /// Inserts a key-value pair like `set`, reporting what the insert did.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    ///
+    /// # Returns
+    /// `SetOutcome::Updated` if the key already held a value, `SetOutcome::Evicted` with the
+    /// displaced entry if one had to make room, `SetOutcome::Rejected` if the value is heavier
+    /// than the cache's maximum weight or the full cache rejected it (see `OverflowPolicy`), or
+    /// `SetOutcome::Inserted` otherwise. Evicting a negative
+    /// entry has no value to hand back, so it is reported as `Inserted`.
+    pub fn set_reporting(&mut self, key: K, value: V) -> SetOutcome<K, V> {
+        let updated = self.peek(&key).is_some();
+        let entry = self.written_entry(&key, value);
+        let Some(evicted) = self.insert_entry(key, entry) else {
+            return SetOutcome::Rejected;
+        };
+        let mut evicted = evicted.into_iter();
+        let first = evicted.next();
+        self.release(evicted);
+        match first {
+            Some((key, value)) => SetOutcome::Evicted { key, value },
+            None if updated => SetOutcome::Updated,
+            None => SetOutcome::Inserted,
+        }
+    }
+
+    /// Inserts a key-value pair like `set`, but hands it back instead of overstepping the cache's
+    /// bounds.
+    ///
+    /// Caches that must stay within a hard budget can use this to fail loudly: the pair is
+    /// rejected if the cache is full and the eviction policy has nothing to evict, whatever the
+    /// `OverflowPolicy`, or if the value alone is heavier than the maximum weight. A rejected
+    /// pair leaves the cache untouched; in particular, an oversized value is turned away before
+    /// anything is evicted, and any value the key already held stays cached.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    ///
+    /// # Returns
+    /// The first entry evicted to make room, if any, or the `InsertError` holding the rejected
+    /// pair.
+    pub fn try_set(&mut self, key: K, value: V) -> Result<Option<(K, V)>, InsertError<K, V>> {
+        let mut evicted = self.try_insert_entry(key, value, Self::written_entry)?.into_iter();
+        let first = evicted.next();
+        self.release(evicted);
+        Ok(first)
+    }
+
+    /// Inserts a key-value pair like `try_set`, handing back every entry evicted to make room, but
+    /// without passing the value to a writer.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    ///
+    /// # Returns
+    /// The evicted key-value pairs, oldest first, or the `InsertError` holding the rejected pair.
+    pub(crate) fn try_insert(&mut self, key: K, value: V) -> Result<Vec<(K, V)>, InsertError<K, V>> {
+        self.try_insert_entry(key, value, |cache, _, value| cache.fresh_entry(value))
+    }
+
+    /// Makes room for a key-value pair, then stores the entry `build` makes for it.
+    ///
+    /// # Returns
+    /// The evicted key-value pairs, oldest first, or the `InsertError` holding the rejected pair.
+    fn try_insert_entry(
+        &mut self,
+        key: K,
+        value: V,
+        build: fn(&Self, &K, V) -> Entry<V>,
+    ) -> Result<Vec<(K, V)>, InsertError<K, V>> {
+        self.apply_pending_accesses();
+        if let Some((weigher, max)) = &self.weigher {
+            let weight = weigher.weigh(&key, &value);
+            if weight > *max {
+                return Err(InsertError::TooLarge { key, value, weight, max: *max });
             }
         }
-        self.store.insert(key.clone(), value);
-        self.eviction_policy.on_insert(&key);
+        let mut evicted = Vec::new();
+        if !self.store.contains_key(&key) && self.store.len() >= self.capacity && !self.evict_one(&mut evicted) {
+            return Err(InsertError::Full { key, value });
+        }
+        let entry = build(self, &key, value);
+        let mut pushed_out = self.insert_entry(key, entry).expect("room was made for the entry");
+        evicted.append(&mut pushed_out);
+        Ok(evicted)
     }
 
-    /// Retrieves a value associated with a given key from the cache.
+    /// Inserts several key-value pairs like `set`, handing back the entries pushed out to make
+    /// room for them.
+    ///
+    /// When loading more items than the cache can hold, the returned entries are the ones that
+    /// didn't fit, which may include items from `items` itself. Values rejected outright, as too
+    /// heavy or by `OverflowPolicy::Reject`, are dropped as with `set` and not returned.
     ///
     /// # Parameters
-    /// * `key`: The key associated with the value to be returned.
+    /// * `items`: The key-value pairs to insert, in order.
     ///
     /// # Returns
-    /// An `Option` containing the value, or `None` if no value is found.
-    pub fn get(&mut self, key: &K) -> Option<&V> {
-        if self.store.contains_key(key) {
-            self.eviction_policy.on_access(key);
-            self.store.get(key)
-        } else {
-            None
+    /// The evicted key-value pairs, in the order they were evicted.
+    pub fn insert_all(&mut self, items: impl IntoIterator<Item = (K, V)>) -> Vec<(K, V)> {
+        let mut evicted = Vec::new();
+        for (key, value) in items {
+            let entry = self.written_entry(&key, value);
+            if let Some(mut pushed_out) = self.insert_entry(key, entry) {
+                evicted.append(&mut pushed_out);
+            }
         }
+        evicted
     }
 
-    /// Removes a key-value pair from the cache.
+    /// Builds the entry for a value being set, passing it on to a write-through writer or marking
+    /// it dirty for a write-back one.
+    fn written_entry(&self, key: &K, value: V) -> Entry<V> {
+        let dirty = match &self.writer {
+            Some((writer, WriteMode::WriteThrough)) => {
+                writer.write(key, &value);
+                false
+            }
+            Some((_, WriteMode::WriteBack)) => true,
+            None => false,
+        };
+        Entry { dirty, ..self.fresh_entry(value) }
+    }
+
+    /// Builds a clean entry for a value, expiring after the default time-to-live.
+    fn fresh_entry(&self, value: V) -> Entry<V> {
+        let now = self.clock.now();
+        let expires_at = self.default_ttl.map(|ttl| now + ttl);
+        Entry { value: Some(value), inserted_at: now, expires_at, dirty: false, weight: 0, epoch: self.epoch }
+    }
+
+    /// Records that a key is absent from the backing store, so lookups can skip it for a while.
+    ///
+    /// The negative entry replaces any cached value, counts toward the capacity like any other
+    /// entry and expires after `ttl`. `lookup` reports it as `Lookup::Negative` and `get` as
+    /// `None`, without falling through to a loader.
     ///
     /// # Parameters
-    /// * `key`: The key to remove.
+    /// * `key`: The key that is known to be absent.
+    /// * `ttl`: How long to remember the absence.
+    pub fn set_negative(&mut self, key: K, ttl: Duration) {
+        let now = self.clock.now();
+        let expires_at = Some(now + ttl);
+        let entry = Entry { value: None, inserted_at: now, expires_at, dirty: false, weight: 0, epoch: self.epoch };
+        let evicted = self.insert_entry(key, entry);
+        self.release(evicted.unwrap_or_default());
+    }
+
+    /// Inserts an entry, evicting others until both the capacity and the weight budget allow it.
     ///
     /// # Returns
-    /// An `Option` containing the removed value if it exists, or `None` if no value is found.
-    pub fn remove(&mut self, key: &K) -> Option<V> {
-        self.eviction_policy.on_remove(key);
-        self.store.remove(key)
+    /// The evicted key-value pairs, oldest first, or `None` if the entry alone is heavier than
+    /// the maximum weight, or the cache is full and `OverflowPolicy::Reject` refused it.
+    fn insert_entry(&mut self, key: K, mut entry: Entry<V>) -> Option<Vec<(K, V)>> {
+        self.apply_pending_accesses();
+        let max_weight = self.weigher.as_ref().map(|(_, max_weight)| *max_weight);
+        if let (Some((weigher, _)), Some(value)) = (&self.weigher, &entry.value) {
+            entry.weight = weigher.weigh(&key, value);
+        }
+        if max_weight.is_some_and(|max_weight| entry.weight > max_weight) {
+            // Drop the old value too, so a rejected update never leaves it behind
+            if self.store.contains_key(&key) {
+                self.eviction_policy.on_remove(&key);
+                if let Some(old) = self.take_entry(&key) {
+                    self.write_back(&key, &old);
+                }
+            }
+            return None;
+        }
+
+        let mut evicted = Vec::new();
+        if !self.store.contains_key(&key) && self.store.len() >= self.capacity && !self.evict_one(&mut evicted) {
+            match self.overflow {
+                OverflowPolicy::Grow => {}
+                OverflowPolicy::Reject => return None,
+                OverflowPolicy::EvictArbitrary => self.evict_arbitrary(&mut evicted),
+            }
+        }
+        if let Some(max_weight) = max_weight {
+            // The entry being replaced doesn't count against the budget
+            while self.weight - self.store.get(&key).map_or(0, |old| old.weight) + entry.weight > max_weight {
+                if !self.evict_one(&mut evicted) {
+                    break;
+                }
+            }
+        }
+        self.put_entry(key.clone(), entry);
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_insert(&key);
+        }
+        if let Some(dropped_key) = self.eviction_policy.on_insert(&key) {
+            if let Some(value) = self.evict_dropped(&dropped_key) {
+                evicted.push((dropped_key, value));
+            }
+        }
+        Some(evicted)
+    }
+
+    /// Evicts the entry chosen by the eviction policy, passing over pinned keys.
+    ///
+    /// # Returns
+    /// `true` if the policy chose an unpinned key, or `false` if it had nothing left to evict.
+    fn evict_one(&mut self, evicted: &mut Vec<(K, V)>) -> bool {
+        let Some(evicted_key) = self.next_victim() else {
+            return false;
+        };
+        let entry = self.take_entry(&evicted_key);
+        if let Some(entry) = &entry {
+            self.write_back(&evicted_key, entry);
+        }
+        self.record_eviction(&evicted_key, entry.as_ref().and_then(|entry| entry.value.as_ref()));
+        if let Some(value) = entry.and_then(|entry| entry.value) {
+            evicted.push((evicted_key, value));
+        }
+        true
+    }
+
+    /// Picks the next key to evict, passing over pinned keys.
+    ///
+    /// With pinned keys around, the victim is the first unpinned key in the policy's `order`,
+    /// taken out with `on_remove` so the pinned keys keep their history. Policies that don't
+    /// report an order are asked to `evict` until they offer an unpinned key, and the pinned keys
+    /// they offered are inserted again, which resets what the policy knew about them.
+    ///
+    /// # Returns
+    /// The key to evict, or `None` if every key the policy tracks is pinned.
+    fn next_victim(&mut self) -> Option<K> {
+        if self.pinned.is_empty() {
+            return self.eviction_policy.evict();
+        }
+        let order = self.eviction_policy.order();
+        if !order.is_empty() {
+            let victim = order.into_iter().find(|key| !self.pinned.contains(key))?;
+            self.eviction_policy.on_remove(&victim);
+            return Some(victim);
+        }
+
+        let mut skipped = Vec::new();
+        let victim = loop {
+            match self.eviction_policy.evict() {
+                Some(key) if self.pinned.contains(&key) => skipped.push(key),
+                victim => break victim,
+            }
+        };
+        for key in skipped {
+            if let Some(dropped_key) = self.eviction_policy.on_insert(&key) {
+                // The store has to follow the policy if taking a pinned key back pushed another out
+                if let Some(value) = self.evict_dropped(&dropped_key) {
+                    self.release([(dropped_key, value)]);
+                }
+            }
+        }
+        victim
+    }
+
+    /// Evicts the entry of a key the eviction policy stopped tracking on its own.
+    ///
+    /// # Returns
+    /// The evicted value, if the key was cached with one.
+    fn evict_dropped(&mut self, key: &K) -> Option<V> {
+        let entry = self.take_entry(key)?;
+        self.write_back(key, &entry);
+        self.record_eviction(key, entry.value.as_ref());
+        entry.value
+    }
+
+    /// Evicts whichever unpinned entry the store yields first, for when the eviction policy has no
+    /// victim.
+    fn evict_arbitrary(&mut self, evicted: &mut Vec<(K, V)>) {
+        let Some(evicted_key) = self.store.keys().find(|key| !self.pinned.contains(*key)).cloned() else {
+            return;
+        };
+        self.eviction_policy.on_remove(&evicted_key);
+        let entry = self.take_entry(&evicted_key);
+        if let Some(entry) = &entry {
+            self.write_back(&evicted_key, entry);
+        }
+        self.record_eviction(&evicted_key, entry.as_ref().and_then(|entry| entry.value.as_ref()));
+        if let Some(value) = entry.and_then(|entry| entry.value) {
+            evicted.push((evicted_key, value));
+        }
+    }
+
+    /// Stores an entry, keeping the total weight up to date.
+    fn put_entry(&mut self, key: K, entry: Entry<V>) {
+        // Replacing an entry isn't removing it, so the key keeps its pin
+        if let Some(old) = self.store.remove(&key) {
+            self.weight -= old.weight;
+        }
+        self.weight += entry.weight;
+        self.store.insert(key, entry);
+    }
+
+    /// Removes an entry from the store, keeping the total weight up to date and dropping its pin.
+    fn take_entry(&mut self, key: &K) -> Option<Entry<V>> {
+        self.pinned.remove(key);
+        let entry = self.store.remove(key)?;
+        self.weight -= entry.weight;
+        Some(entry)
+    }
+
+    /// Loads entries into a cold cache, such as when restoring a snapshot.
+    ///
+    /// Entries are replayed through the eviction policy in iteration order, so the last primed
+    /// entry ends up most recent. Once the cache is full, or the next entry would exceed the
+    /// maximum weight, the remaining entries are dropped instead of evicting earlier ones. Unlike
+    /// `set`, nothing is evicted and no stats or observer events are recorded, which assumes the
+    /// cache starts out empty.
+    ///
+    /// # Parameters
+    /// * `entries`: The key-value pairs to load, oldest first.
+    pub fn prime(&mut self, entries: impl IntoIterator<Item = (K, V)>) {
+        self.apply_pending_accesses();
+        for (key, value) in entries {
+            if !self.store.contains_key(&key) && self.store.len() >= self.capacity {
+                break;
+            }
+            let weight = self.weigher.as_ref().map_or(0, |(weigher, _)| weigher.weigh(&key, &value));
+            let replaced = self.store.get(&key).map_or(0, |old| old.weight);
+            if self.weigher.as_ref().is_some_and(|(_, max_weight)| self.weight - replaced + weight > *max_weight) {
+                break;
+            }
+            let now = self.clock.now();
+            let expires_at = self.default_ttl.map(|ttl| now + ttl);
+            self.put_entry(
+                key.clone(),
+                Entry { value: Some(value), inserted_at: now, expires_at, dirty: false, weight, epoch: self.epoch },
+            );
+            if let Some(dropped_key) = self.eviction_policy.on_insert(&key) {
+                self.take_entry(&dropped_key);
+            }
+        }
+    }
+
+    /// Retrieves a value associated with a given key from the cache.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if no value is found, it has expired or the key
+    /// is cached as absent. With a loader installed, a miss is filled from the backing store when
+    /// it has the key.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        match self.lookup(key) {
+            Lookup::Hit(value) => Some(value),
+            Lookup::Negative | Lookup::Miss => None,
+        }
+    }
+
+    /// Retrieves a value like `get`, first checking that it is still usable.
+    ///
+    /// Some values go bad because of state outside the cache, such as a file handle that was
+    /// closed. A cached value that fails `is_valid` is removed, as with `remove`, and the lookup
+    /// then proceeds as a miss: it is counted as one and, with a loader installed, filled from
+    /// the backing store without checking the loaded value again.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `is_valid`: Checks whether a cached value can still be used.
+    ///
+    /// # Returns
+    /// An `Option` containing a valid value, or `None` under the conditions where `get` returns
+    /// `None` or if the cached value was invalid.
+    pub fn get_valid<F>(&mut self, key: &K, is_valid: F) -> Option<&V>
+    where
+        F: Fn(&V) -> bool,
+    {
+        if self.peek(key).is_some_and(|value| !is_valid(value)) {
+            self.remove(key);
+        }
+        self.get(key)
+    }
+
+    /// Looks up a key, telling a cached absence apart from a plain miss.
+    ///
+    /// A negative entry counts as a hit in the stats, since the cache answered the lookup.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// `Lookup::Hit` with the value, `Lookup::Negative` if the key was recorded as absent with
+    /// `set_negative`, or `Lookup::Miss` otherwise. With a loader installed, a miss is filled from
+    /// the backing store when it has the key.
+    pub fn lookup(&mut self, key: &K) -> Lookup<'_, V> {
+        self.apply_pending_accesses();
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        match self.store.get(key) {
+            Some(entry) if entry.is_expired(now, epoch) => {
+                self.eviction_policy.on_remove(key);
+                if let Some(entry) = self.take_entry(key) {
+                    self.write_back(key, &entry);
+                    // Entries from an earlier epoch were invalidated rather than timed out
+                    if entry.epoch == epoch {
+                        self.record_expiration();
+                    }
+                }
+                self.record_miss(key);
+                self.load(key).map_or(Lookup::Miss, Lookup::Hit)
+            }
+            Some(_) => {
+                self.promote(key);
+                self.record_hit(key);
+                match self.store.get(key).and_then(|entry| entry.value.as_ref()) {
+                    Some(value) => Lookup::Hit(value),
+                    None => Lookup::Negative,
+                }
+            }
+            None => {
+                self.record_miss(key);
+                self.load(key).map_or(Lookup::Miss, Lookup::Hit)
+            }
+        }
+    }
+
+    /// Marks an entry as used without reading its value.
+    ///
+    /// The eviction policy sees an access, so LRU treats the key as most recently used and LFU
+    /// bumps its frequency. Stats aren't updated, since nothing was looked up.
+    ///
+    /// # Parameters
+    /// * `key`: The key to promote.
+    ///
+    /// # Returns
+    /// `true` if the key held an unexpired entry and was promoted, otherwise `false`.
+    pub fn touch(&mut self, key: &K) -> bool {
+        self.apply_pending_accesses();
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        if self.store.get(key).is_none_or(|entry| entry.is_expired(now, epoch)) {
+            return false;
+        }
+        self.eviction_policy.on_access(key);
+        true
+    }
+
+    /// Retrieves a copy of the value associated with a given key, so the cache isn't left borrowed.
+    ///
+    /// The lookup counts as an access, exactly as with `get`.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a clone of the value, or `None` if `get` would find nothing.
+    pub fn get_cloned(&mut self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.get(key).cloned()
+    }
+
+    /// Retrieves the value for a key, loading and caching it from the backing store on a miss.
+    ///
+    /// This is the cache-aside pattern in one call: a hit is returned as is, a miss asks the
+    /// installed `Loader` and caches what it returns, and a key the loader doesn't have stays
+    /// uncached so a later call asks again. `get` already reads through a loader the same way;
+    /// this name makes the intent explicit at call sites that rely on it.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the cached or loaded value, or `None` if neither the cache nor the
+    /// loader has it, or if the key is cached as absent with `set_negative`.
+    pub fn get_or_load(&mut self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    /// Retrieves mutable references to the values of several distinct keys at once.
+    ///
+    /// Nothing is returned unless every key holds an unexpired value and no key appears twice,
+    /// in which case each key counts as a hit. Under `WriteMode::WriteBack` the returned entries
+    /// are marked for writing, since the caller may change them. Finding the entries visits the
+    /// whole cache, so this suits batches rather than single lookups.
+    ///
+    /// # Parameters
+    /// * `keys`: The keys associated with the values to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a mutable reference to each value, in the same order as `keys`, or
+    /// `None` if any key is missing or duplicated.
+    pub fn get_many_mut(&mut self, keys: &[K]) -> Option<Vec<&mut V>> {
+        self.apply_pending_accesses();
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        let mut positions = HashMap::with_capacity(keys.len());
+        for (position, key) in keys.iter().enumerate() {
+            let live = self.store.get(key).is_some_and(|entry| entry.value.is_some() && !entry.is_expired(now, epoch));
+            if !live || positions.insert(key, position).is_some() {
+                return None;
+            }
+        }
+        for key in keys {
+            self.promote(key);
+            self.record_hit(key);
+        }
+
+        let write_back = matches!(self.writer, Some((_, WriteMode::WriteBack)));
+        let mut values: Vec<Option<&mut V>> = keys.iter().map(|_| None).collect();
+        for (key, entry) in self.store.iter_mut() {
+            if let Some(&position) = positions.get(key) {
+                entry.dirty |= write_back;
+                values[position] = entry.value.as_mut();
+            }
+        }
+        values.into_iter().collect()
+    }
+
+    /// Retrieves a mutable reference to the value for a key, inserting `V::default()` on a miss.
+    ///
+    /// The default is inserted through `set`, so it may evict another entry. Under
+    /// `WriteMode::WriteBack` the entry is marked for writing, since the caller may change it;
+    /// a write-through writer only sees the inserted default.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// A mutable reference to the cached or newly inserted value.
+    ///
+    /// # Panics
+    /// Panics if the value has to be inserted but the cache refuses it: when it is heavier than
+    /// the weigher's maximum weight, or when the cache is full, its `OverflowPolicy` is `Reject`
+    /// and the eviction policy has no key to evict, such as when every entry is pinned.
+    pub fn get_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default,
+    {
+        if self.get(&key).is_none() {
+            self.set(key.clone(), V::default());
+        }
+        let write_back = matches!(self.writer, Some((_, WriteMode::WriteBack)));
+        let entry = self.store.get_mut(&key).expect("cache refused the inserted value");
+        entry.dirty |= write_back;
+        entry.value.get_or_insert_with(V::default)
+    }
+
+    /// Retrieves the value for a key, inserting `default` on a miss.
+    ///
+    /// `default` is built by the caller before the lookup and simply dropped on a hit, so this
+    /// suits cheap defaults; use `get_or_insert_with` to only build the value on a miss.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `default`: The value to insert if the key isn't cached.
+    ///
+    /// # Returns
+    /// A reference to the cached or newly inserted value.
+    ///
+    /// # Panics
+    /// Panics if the value has to be inserted but the cache refuses it: when it is heavier than
+    /// the weigher's maximum weight, or when the cache is full, its `OverflowPolicy` is `Reject`
+    /// and the eviction policy has no key to evict, such as when every entry is pinned.
+    pub fn get_or_insert(&mut self, key: K, default: V) -> &V {
+        self.get_or_insert_with(key, || default)
+    }
+
+    /// Retrieves the value for a key, computing and inserting it on a miss.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `f`: Computes the value if the key isn't cached. Only called on a miss.
+    ///
+    /// # Returns
+    /// A reference to the cached or newly inserted value.
+    ///
+    /// # Panics
+    /// Panics if the value has to be inserted but the cache refuses it: when it is heavier than
+    /// the weigher's maximum weight, or when the cache is full, its `OverflowPolicy` is `Reject`
+    /// and the eviction policy has no key to evict, such as when every entry is pinned.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> &V
+    where
+        F: FnOnce() -> V,
+    {
+        match self.try_get_or_insert_with(key, || Ok::<V, Infallible>(f())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Retrieves the value for a key, computing and inserting it on a miss with a fallible function.
+    ///
+    /// If `f` fails, nothing is inserted or evicted and the error is returned.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `f`: Computes the value if the key isn't cached. Only called on a miss.
+    ///
+    /// # Returns
+    /// A reference to the cached or newly inserted value, or the error returned by `f`.
+    ///
+    /// # Panics
+    /// Panics if the value has to be inserted but the cache refuses it: when it is heavier than
+    /// the weigher's maximum weight, or when the cache is full, its `OverflowPolicy` is `Reject`
+    /// and the eviction policy has no key to evict, such as when every entry is pinned.
+    pub fn try_get_or_insert_with<F, E>(&mut self, key: K, f: F) -> Result<&V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        if self.get(&key).is_none() {
+            let value = f()?;
+            self.set(key.clone(), value);
+        }
+        Ok(self.store.get(&key).and_then(|entry| entry.value.as_ref()).expect("cache refused the inserted value"))
+    }
+
+    /// Retrieves the value for a key, awaiting an asynchronous loader and inserting its result on
+    /// a miss.
+    ///
+    /// The future is only created and awaited on a miss. Concurrent callers that miss on the same
+    /// key are not deduplicated; each runs its own loader.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `f`: Creates the future that loads the value if the key isn't cached.
+    ///
+    /// # Returns
+    /// A reference to the cached or newly inserted value.
+    ///
+    /// # Panics
+    /// Panics if the value has to be inserted but the cache refuses it: when it is heavier than
+    /// the weigher's maximum weight, or when the cache is full, its `OverflowPolicy` is `Reject`
+    /// and the eviction policy has no key to evict, such as when every entry is pinned.
+    #[cfg(feature = "async")]
+    pub async fn get_or_insert_with_async<F, Fut>(&mut self, key: K, f: F) -> &V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        if self.get(&key).is_none() {
+            let value = f().await;
+            self.set(key.clone(), value);
+        }
+        self.store.get(&key).and_then(|entry| entry.value.as_ref()).expect("cache refused the inserted value")
+    }
+
+    /// Retrieves a value through a shared reference, deferring the access bookkeeping.
+    ///
+    /// The access is queued and only reported to the eviction policy at the start of the next
+    /// `set`, `get` or `remove`, so several lookups can hold their results at once. Hits and
+    /// misses are likewise reflected in `stats` and reported to the observer once the queue is
+    /// applied.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if no value is found.
+    pub fn get_shared(&self, key: &K) -> Option<&V> {
+        let value = self.peek(key);
+        self.pending_lookups.push(key.clone(), value.is_some());
+        value
+    }
+
+    /// Looks up a value without recording an access or updating the stats.
+    pub(crate) fn peek(&self, key: &K) -> Option<&V> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        self.store.get(key).filter(|entry| !entry.is_expired(now, epoch))?.value.as_ref()
+    }
+
+    /// Checks whether every key in a batch is cached, without recording any access.
+    ///
+    /// # Parameters
+    /// * `keys`: The keys to check.
+    ///
+    /// # Returns
+    /// `true` if all keys hold unexpired values. An empty slice returns `true`, since nothing in
+    /// it needs fetching.
+    pub fn contains_all(&self, keys: &[K]) -> bool {
+        keys.iter().all(|key| self.peek(key).is_some())
+    }
+
+    /// Checks whether any key in a batch is cached, without recording any access.
+    ///
+    /// # Parameters
+    /// * `keys`: The keys to check.
+    ///
+    /// # Returns
+    /// `true` if at least one key holds an unexpired value. An empty slice returns `false`.
+    pub fn contains_any(&self, keys: &[K]) -> bool {
+        keys.iter().any(|key| self.peek(key).is_some())
+    }
+
+    /// Returns when an entry will expire.
+    ///
+    /// # Parameters
+    /// * `key`: The key to inspect.
+    ///
+    /// # Returns
+    /// The expiry time, or `None` if the key is absent, already expired or has no time-to-live.
+    pub fn expires_at(&self, key: &K) -> Option<Instant> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        self.store.get(key).filter(|entry| !entry.is_expired(now, epoch))?.expires_at
+    }
+
+    /// Returns how long an entry has left before it expires.
+    ///
+    /// # Parameters
+    /// * `key`: The key to inspect.
+    ///
+    /// # Returns
+    /// The remaining time-to-live, or `None` if the key is absent, already expired or has no
+    /// time-to-live.
+    pub fn ttl_remaining(&self, key: &K) -> Option<Duration> {
+        let expires_at = self.expires_at(key)?;
+        Some(expires_at.duration_since(self.clock.now()))
+    }
+
+    /// Finds entries that will expire soon, so they can be refreshed before they do.
+    ///
+    /// Nothing is refreshed or recorded; callers recompute the values and `set` them again.
+    ///
+    /// # Parameters
+    /// * `window`: How close to expiry an entry must be to count as stale.
+    ///
+    /// # Returns
+    /// The keys of unexpired entries that expire within `window`, in arbitrary order.
+    pub fn stale_keys(&self, window: Duration) -> Vec<K> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        self.store
+            .iter()
+            .filter(|(_, entry)| {
+                entry.epoch == epoch
+                    && entry.expires_at.is_some_and(|expires_at| expires_at > now && expires_at - now <= window)
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Counts the cached entries by how long ago they were stored, for capacity tuning.
+    ///
+    /// An entry's age runs from the last time its key was set, or loaded, to now. Each entry is
+    /// counted in the first bucket whose bound its age doesn't exceed, so with bounds of one
+    /// second and one minute the counts are of entries up to a second old, then over a second
+    /// and up to a minute old, then older than that. Expired entries aren't counted.
+    ///
+    /// # Parameters
+    /// * `buckets`: The upper bound of each bucket's ages, in ascending order.
+    ///
+    /// # Returns
+    /// One count per bucket, followed by the count of entries older than every bound.
+    pub fn age_histogram(&self, buckets: &[Duration]) -> Vec<usize> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        let mut counts = vec![0; buckets.len() + 1];
+        for (_, entry) in self.store.iter().filter(|(_, entry)| !entry.is_expired(now, epoch)) {
+            let age = now.duration_since(entry.inserted_at);
+            counts[buckets.partition_point(|&bound| bound < age)] += 1;
+        }
+        counts
+    }
+
+    /// Removes every entry whose time-to-live has run out, along with entries invalidated by
+    /// `bump_epoch`.
+    ///
+    /// Expired entries are otherwise only removed when a lookup finds them, so they keep counting
+    /// toward the capacity. Each removal of a timed-out entry is counted in
+    /// `CacheStats::ttl_expirations`.
+    ///
+    /// # Returns
+    /// The number of entries removed.
+    pub fn purge_expired(&mut self) -> usize {
+        self.apply_pending_accesses();
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        let expired: Vec<K> =
+            self.store.iter().filter(|(_, entry)| entry.is_expired(now, epoch)).map(|(key, _)| key.clone()).collect();
+        for key in &expired {
+            self.eviction_policy.on_remove(key);
+            if let Some(entry) = self.take_entry(key) {
+                self.write_back(key, &entry);
+                if entry.epoch == epoch {
+                    self.record_expiration();
+                }
+            }
+        }
+        expired.len()
+    }
+
+    /// Removes the entries the eviction policy gives up through `EvictionPolicy::sweep`.
+    ///
+    /// Policies such as `MaxIdle` use this to drop entries by age even while the cache has room.
+    /// Swept entries expire by time rather than to make room, so they are counted in
+    /// `CacheStats::ttl_expirations`.
+    ///
+    /// # Returns
+    /// The removed key-value pairs, in the order the policy gave them up.
+    pub fn sweep(&mut self) -> Vec<(K, V)> {
+        self.apply_pending_accesses();
+        let mut swept = Vec::new();
+        for key in self.eviction_policy.sweep() {
+            if let Some(entry) = self.take_entry(&key) {
+                self.write_back(&key, &entry);
+                self.record_expiration();
+                if let Some(value) = entry.value {
+                    swept.push((key, value));
+                }
+            }
+        }
+        swept
+    }
+
+    /// Removes a key-value pair from the cache.
+    ///
+    /// The eviction policy is only told about the removal if the key was cached, so removing an
+    /// absent key leaves the policy untouched.
+    ///
+    /// # Parameters
+    /// * `key`: The key to remove.
+    ///
+    /// # Returns
+    /// An `Option` containing the removed value if it exists, or `None` if no value is found.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.apply_pending_accesses();
+        let entry = self.take_entry(key)?;
+        self.eviction_policy.on_remove(key);
+        entry.value
+    }
+
+    /// Keeps a cached entry from being evicted until it is unpinned.
+    ///
+    /// A pinned entry still counts toward the capacity. When every entry the eviction policy
+    /// could evict is pinned, a full cache falls back to its `OverflowPolicy`, and `resize`
+    /// leaves the cache over its new capacity. The pin lasts until `unpin`, or until the entry
+    /// leaves the cache by being removed, cleared or expiring.
+    ///
+    /// Pinned entries keep their eviction policy state when passed over, as long as the policy
+    /// reports its eviction `order`. Other policies forget what they knew about a pinned key each
+    /// time they offer it as a victim.
+    ///
+    /// # Parameters
+    /// * `key`: The key to pin.
+    ///
+    /// # Returns
+    /// `true` if the key is cached and is now pinned, or `false` if it isn't cached.
+    pub fn pin(&mut self, key: &K) -> bool {
+        if !self.store.contains_key(key) {
+            return false;
+        }
+        self.pinned.insert(key.clone());
+        true
+    }
+
+    /// Lets a pinned entry be evicted again.
+    ///
+    /// # Parameters
+    /// * `key`: The key to unpin.
+    ///
+    /// # Returns
+    /// `true` if the key was pinned.
+    pub fn unpin(&mut self, key: &K) -> bool {
+        self.pinned.remove(key)
+    }
+
+    /// Checks whether a key is pinned.
+    ///
+    /// # Parameters
+    /// * `key`: The key to check.
+    ///
+    /// # Returns
+    /// `true` if the key is pinned.
+    pub fn is_pinned(&self, key: &K) -> bool {
+        self.pinned.contains(key)
+    }
+
+    /// Removes several key-value pairs from the cache.
+    ///
+    /// # Parameters
+    /// * `keys`: The keys to remove.
+    ///
+    /// # Returns
+    /// The removed value for each key, in the same order as `keys`, with `None` for absent keys.
+    pub fn remove_many(&mut self, keys: &[K]) -> Vec<Option<V>> {
+        keys.iter().map(|key| self.remove(key)).collect()
+    }
+
+    /// Removes entries in the order the eviction policy would evict them.
+    ///
+    /// Each step asks the policy to evict a key and removes it from the cache, so unlike
+    /// `into_iter` the entries come out least valuable first. Like `remove`, the entries are
+    /// handed to the caller rather than counted as evictions or written back. Expired and
+    /// negative entries are removed without being yielded. Pinned entries are passed over and stay
+    /// cached, as do entries the iterator doesn't reach before being dropped.
+    ///
+    /// # Returns
+    /// An iterator yielding each removed key-value pair, in eviction order.
+    pub fn evict_iter(&mut self) -> impl Iterator<Item = (K, V)> + '_ {
+        self.apply_pending_accesses();
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        core::iter::from_fn(move || loop {
+            let key = self.next_victim()?;
+            let Some(entry) = self.take_entry(&key) else {
+                continue;
+            };
+            if let (false, Some(value)) = (entry.is_expired(now, epoch), entry.value) {
+                return Some((key, value));
+            }
+        })
+    }
+
+    /// Writes every value still held back by a `WriteMode::WriteBack` writer.
+    ///
+    /// `remove` and `clear` discard entries without writing them, so call this first if their
+    /// pending writes should be kept.
+    pub fn flush(&mut self) {
+        if let Some((writer, _)) = &self.writer {
+            for (key, entry) in self.store.iter_mut() {
+                if let (true, Some(value)) = (entry.dirty, &entry.value) {
+                    writer.write(key, value);
+                    entry.dirty = false;
+                }
+            }
+        }
+    }
+
+    /// Returns the keys whose values are still held back by a `WriteMode::WriteBack` writer.
+    ///
+    /// These are the entries the next `flush` would write, in arbitrary order.
+    ///
+    /// # Returns
+    /// The keys of the dirty entries.
+    pub fn dirty_keys(&self) -> Vec<K> {
+        self.store
+            .iter()
+            .filter(|(_, entry)| entry.dirty && entry.value.is_some())
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Removes entries the eviction policy no longer tracks.
+    ///
+    /// Such entries could never be evicted, so `get` would keep serving them indefinitely. The
+    /// built-in policies report every key they drop through `EvictionPolicy::on_insert`, so this
+    /// only finds anything when a custom policy loses keys without reporting them. It checks
+    /// every entry, so call it occasionally rather than on every lookup.
+    ///
+    /// # Returns
+    /// The number of entries removed.
+    pub fn validate(&mut self) -> usize {
+        self.apply_pending_accesses();
+        let untracked: Vec<K> =
+            self.store.keys().filter(|key| !self.eviction_policy.contains(key)).cloned().collect();
+        for key in &untracked {
+            if let Some(entry) = self.take_entry(key) {
+                self.write_back(key, &entry);
+            }
+        }
+        untracked.len()
+    }
+
+    /// Removes every entry whose key starts with a given prefix.
+    ///
+    /// # Parameters
+    /// * `prefix`: The prefix to match, such as `"user:42:"`.
+    ///
+    /// # Returns
+    /// The number of entries removed.
+    pub fn invalidate_prefix(&mut self, prefix: &str) -> usize
+    where
+        K: AsRef<str>,
+    {
+        let keys: Vec<K> = self
+            .store
+            .keys()
+            .filter(|key| key.as_ref().starts_with(prefix))
+            .cloned()
+            .collect();
+        for key in &keys {
+            self.remove(key);
+        }
+        keys.len()
+    }
+
+    /// Invalidates every cached entry at once, without visiting them.
+    ///
+    /// Entries stored before the call are treated like expired ones from then on: lookups miss
+    /// and remove them as they find them, and `purge_expired` removes the rest. Until then they
+    /// still count toward `len` and the capacity, and the eviction policy may evict them as usual.
+    /// Unlike `clear`, this takes constant time however large the cache is.
+    pub fn bump_epoch(&mut self) {
+        self.epoch += 1;
+    }
+
+    /// Removes all key-value pairs from the cache.
+    pub fn clear(&mut self) {
+        self.pending_lookups.get_mut().clear();
+        for key in self.store.keys() {
+            self.eviction_policy.on_remove(key);
+        }
+        self.eviction_policy.reset();
+        self.store.clear();
+        self.weight = 0;
+        self.pinned.clear();
+    }
+
+    /// Releases spare capacity left behind after many entries were removed or evicted.
+    ///
+    /// Hash maps keep their allocation when entries leave, so a cache that once held far more
+    /// entries than it does now can hold on to a lot of memory. This shrinks the store and asks the
+    /// eviction policy to shrink its own collections; the cached entries are unaffected.
+    pub fn shrink_to_fit(&mut self) {
+        self.store.shrink_to_fit();
+        self.eviction_policy.shrink_to_fit();
+    }
+
+    /// Returns a read-only view of the cache, for code that must not change it.
+    ///
+    /// The view can only look entries up without recording an access, so handing it out leaves
+    /// both the entries and the eviction order exactly as they were.
+    ///
+    /// # Returns
+    /// A `CacheView` borrowing the cache.
+    pub fn view(&self) -> CacheView<'_, K, V, P> {
+        CacheView { cache: self }
+    }
+
+    /// Returns an iterator over the cached keys, in arbitrary order.
+    ///
+    /// # Returns
+    /// An iterator yielding each key with an unexpired value.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        self.store
+            .iter()
+            .filter(move |(_, entry)| entry.value.is_some() && !entry.is_expired(now, epoch))
+            .map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over mutable references to the cached values, in arbitrary order.
+    ///
+    /// Updating values this way doesn't count as an access, so the eviction order is unchanged.
+    /// Under `WriteMode::WriteBack` every visited entry is marked for writing.
+    ///
+    /// # Returns
+    /// An iterator yielding each unexpired value.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        let now = self.clock.now();
+        let epoch = self.epoch;
+        let write_back = matches!(self.writer, Some((_, WriteMode::WriteBack)));
+        self.store.iter_mut().filter(move |(_, entry)| !entry.is_expired(now, epoch)).filter_map(move |(_, entry)| {
+            let value = entry.value.as_mut()?;
+            entry.dirty |= write_back;
+            Some(value)
+        })
+    }
+
+    /// Changes the capacity of the cache, evicting entries if it shrinks below the current size.
+    ///
+    /// Victims are chosen by the eviction policy exactly as they would be by `set`, and are
+    /// counted as evictions.
+    ///
+    /// # Parameters
+    /// * `capacity`: The new maximum number of items the cache can hold.
+    ///
+    /// # Returns
+    /// The evicted key-value pairs, in eviction order. Empty if the cache didn't shrink below its
+    /// current size.
+    ///
+    /// # Panics
+    /// Panics if the eviction policy manages its own capacity (see `EvictionPolicy::capacity`),
+    /// since the policy's segments can't be resized along with the cache.
+    pub fn resize(&mut self, capacity: usize) -> Vec<(K, V)> {
+        assert!(
+            self.eviction_policy.capacity().is_none(),
+            "cannot resize a cache whose eviction policy manages its own capacity"
+        );
+        self.apply_pending_accesses();
+        self.capacity = capacity;
+        let mut evicted = Vec::new();
+        while self.store.len() > self.capacity {
+            // Pinned keys have to be passed over one victim at a time
+            let evicted_keys = if self.pinned.is_empty() {
+                self.eviction_policy.evict_n(self.store.len() - self.capacity)
+            } else {
+                self.next_victim().into_iter().collect()
+            };
+            if evicted_keys.is_empty() {
+                break;
+            }
+            for evicted_key in evicted_keys {
+                if let Some(entry) = self.take_entry(&evicted_key) {
+                    self.write_back(&evicted_key, &entry);
+                    self.record_eviction(&evicted_key, entry.value.as_ref());
+                    if let Some(value) = entry.value {
+                        evicted.push((evicted_key, value));
+                    }
+                }
+            }
+        }
+        evicted
+    }
+
+    /// Dumps the metadata the eviction policy tracks for each key.
+    ///
+    /// Accesses queued by `get_shared` aren't reflected until the next `set`, `get` or `remove`.
+    ///
+    /// # Returns
+    /// Each tracked key with its metadata, in arbitrary order. See `EvictionPolicy::debug_state`.
+    pub fn debug_policy_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.eviction_policy.debug_state()
+    }
+
+    /// Lists the cached keys in the order the eviction policy would evict them, for debugging.
+    ///
+    /// Accesses queued by `get_shared` aren't reflected until the next `set`, `get` or `remove`.
+    ///
+    /// # Returns
+    /// The keys, next eviction candidate first, or nothing if the policy doesn't report an order.
+    /// See `EvictionPolicy::order`.
+    pub fn eviction_order(&self) -> Vec<K> {
+        self.eviction_policy.order()
+    }
+
+    /// Estimates how valuable the eviction policy considers a key, such as for deciding which
+    /// keys to replicate elsewhere.
+    ///
+    /// Accesses queued by `get_shared` aren't reflected until the next `set`, `get` or `remove`.
+    ///
+    /// # Parameters
+    /// * `key`: The key to score.
+    ///
+    /// # Returns
+    /// A score from 0 to 1, or `None` if the key isn't cached or the policy doesn't score keys.
+    /// See `EvictionPolicy::hotness`.
+    pub fn hotness(&self, key: &K) -> Option<f64> {
+        self.eviction_policy.hotness(key)
+    }
+
+    /// Returns the name of the eviction policy, for logging and metrics labels.
+    ///
+    /// # Returns
+    /// The name reported by `EvictionPolicy::name`.
+    pub fn policy_name(&self) -> &'static str {
+        self.eviction_policy.name()
+    }
+
+    /// Returns the maximum number of key-value pairs the cache holds before evicting.
+    ///
+    /// # Returns
+    /// The capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the total weight of the cached entries, as measured by the cache's weigher.
+    ///
+    /// # Returns
+    /// The total weight, or zero if the cache has no weigher.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// Returns the number of key-value pairs in the cache.
+    ///
+    /// # Returns
+    /// The number of entries, including negative entries recorded with `set_negative`.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Checks to see if the cache is empty.
+    ///
+    /// # Returns
+    /// `true` if the cache holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Estimates the memory used by the cache's entries, in bytes.
+    ///
+    /// Sums `SizeOf::size_of` over every key and value, plus the per-entry metadata the cache
+    /// keeps alongside each value. Spare hash table capacity and the eviction policy's own
+    /// bookkeeping are not included. For key or value types without a `SizeOf` impl, such as
+    /// types from other crates, use `memory_estimate_with`.
+    ///
+    /// # Returns
+    /// The approximate number of bytes used.
+    pub fn memory_estimate(&self) -> usize
+    where
+        K: SizeOf,
+        V: SizeOf,
+    {
+        self.memory_estimate_with(SizeOf::size_of, SizeOf::size_of)
+    }
+
+    /// Estimates the memory used by the cache's entries, in bytes, sizing keys and values with the
+    /// given functions.
+    ///
+    /// Works like `memory_estimate`, for types that don't implement `SizeOf`. Passing
+    /// `core::mem::size_of_val` counts only the inline size of each key or value.
+    ///
+    /// # Parameters
+    /// * `key_size`: Returns the approximate size of a key in bytes.
+    /// * `value_size`: Returns the approximate size of a value in bytes.
+    ///
+    /// # Returns
+    /// The approximate number of bytes used.
+    pub fn memory_estimate_with(&self, key_size: impl Fn(&K) -> usize, value_size: impl Fn(&V) -> usize) -> usize {
+        let entry_overhead = mem::size_of::<Entry<V>>() - mem::size_of::<V>();
+        mem::size_of::<Self>()
+            + self
+                .store
+                .iter()
+                .map(|(key, entry)| {
+                    // A negative entry still reserves inline space for a value
+                    let value_size = entry.value.as_ref().map_or(mem::size_of::<V>(), &value_size);
+                    key_size(key) + value_size + entry_overhead
+                })
+                .sum::<usize>()
+    }
+
+    /// Returns the hit, miss and eviction counters recorded so far.
+    ///
+    /// # Returns
+    /// A reference to the cache's `CacheStats`.
+    pub fn stats(&self) -> &CacheStats {
+        &self.stats
+    }
+
+    /// Returns the counters recorded so far and resets them to zero in one step.
+    ///
+    /// Taking the stats at the end of each reporting interval yields the events of that interval
+    /// alone, with none counted twice or lost between reading and resetting. Lookups queued by
+    /// `get_shared` are counted first.
+    ///
+    /// # Returns
+    /// The `CacheStats` recorded since the cache was created or the stats were last taken.
+    pub fn take_stats(&mut self) -> CacheStats {
+        self.apply_pending_accesses();
+        mem::take(&mut self.stats)
+    }
+
+    /// Reports accesses queued by `get_shared` to the eviction policy and the stats.
+    fn apply_pending_accesses(&mut self) {
+        for (key, hit) in mem::take(self.pending_lookups.get_mut()) {
+            if !hit {
+                self.record_miss(&key);
+                continue;
+            }
+            // The key may have been removed since it was read
+            if self.store.contains_key(&key) {
+                self.promote(&key);
+            }
+            self.record_hit(&key);
+        }
+    }
+
+    /// Reports a lookup's access to the eviction policy, unless tracking is off.
+    fn promote(&mut self, key: &K) {
+        if self.tracking {
+            self.eviction_policy.on_access(key);
+        }
+    }
+
+    /// Turns the reporting of lookups to the eviction policy on or off.
+    ///
+    /// While tracking is off, lookups still return values and update the stats but don't promote
+    /// the keys they hit, so a bulk pass over the data leaves LRU order and LFU counts as they
+    /// were. `touch` still promotes, since that is its only effect. Tracking is on by default.
+    ///
+    /// # Parameters
+    /// * `enabled`: Whether lookups should promote the keys they hit.
+    pub fn set_tracking(&mut self, enabled: bool) {
+        // Queued accesses belong to the mode they were made in
+        self.apply_pending_accesses();
+        self.tracking = enabled;
+    }
+
+    /// Runs a closure with tracking turned off, such as for a batch job reading every entry.
+    ///
+    /// Tracking is restored to its previous setting afterwards. See `set_tracking`.
+    ///
+    /// # Parameters
+    /// * `f`: The closure to run against the cache.
+    ///
+    /// # Returns
+    /// Whatever `f` returns.
+    pub fn with_scan_mode<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let tracking = self.tracking;
+        self.set_tracking(false);
+        let result = f(self);
+        self.set_tracking(tracking);
+        result
+    }
+
+    /// Fills a miss from the loader, if one is installed.
+    fn load(&mut self, key: &K) -> Option<&V> {
+        let value = self.loader.as_ref()?.load(key)?;
+        let entry = self.fresh_entry(value);
+        let evicted = self.insert_entry(key.clone(), entry).unwrap_or_default();
+        self.release(evicted);
+        self.store.get(key)?.value.as_ref()
+    }
+
+    /// Writes an entry leaving the cache if its value hasn't been written yet.
+    fn write_back(&self, key: &K, entry: &Entry<V>) {
+        if let Some((writer, _)) = &self.writer {
+            if let (true, Some(value)) = (entry.dirty, &entry.value) {
+                writer.write(key, value);
+            }
+        }
+    }
+
+    /// Records a lookup that found a value.
+    fn record_hit(&mut self, key: &K) {
+        self.record(|stats| stats.hits += 1);
+        self.record_outcome(true);
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_hit(key);
+        }
+    }
+
+    /// Records a lookup that found nothing.
+    fn record_miss(&mut self, key: &K) {
+        self.record(|stats| stats.misses += 1);
+        self.record_outcome(false);
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_miss(key);
+        }
+    }
+
+    /// Remembers a lookup's outcome and grows a full cache that has been missing too often.
+    fn record_outcome(&mut self, hit: bool) {
+        let Some((growth, recent)) = self.growth.as_mut() else {
+            return;
+        };
+        if recent.len() == growth.window {
+            recent.pop_front();
+        }
+        recent.push_back(hit);
+        if recent.len() < growth.window || self.store.len() < self.capacity || self.capacity >= growth.max_capacity {
+            return;
+        }
+        let misses = recent.iter().filter(|&&hit| !hit).count();
+        if misses as f64 / growth.window as f64 > growth.miss_rate {
+            let grown = (self.capacity as f64 * growth.factor) as usize;
+            self.capacity = grown.max(self.capacity + 1).min(growth.max_capacity);
+            recent.clear();
+        }
+    }
+
+    /// Records an entry removed to make room for another, before its value is moved or dropped.
+    fn record_eviction(&mut self, key: &K, value: Option<&V>) {
+        self.record(|stats| {
+            stats.evictions += 1;
+            stats.capacity_evictions += 1;
+        });
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_evict(key);
+        }
+        if let (Some(on_evict), Some(value)) = (self.on_evict.as_mut(), value) {
+            on_evict(key, value);
+        }
+    }
+
+    /// Hands evicted entries that no caller receives to the owning eviction callback, if any.
+    fn release(&mut self, evicted: impl IntoIterator<Item = (K, V)>) {
+        if let Some(on_evict_owned) = self.on_evict_owned.as_mut() {
+            for (key, value) in evicted {
+                on_evict_owned(key, value);
+            }
+        }
+    }
+
+    /// Records an entry removed because its time-to-live ran out.
+    fn record_expiration(&mut self) {
+        self.record(|stats| {
+            stats.evictions += 1;
+            stats.ttl_expirations += 1;
+        });
+    }
+
+    /// Updates the stats, unless recording has been disabled.
+    fn record(&mut self, update: impl FnOnce(&mut CacheStats)) {
+        if self.stats_enabled {
+            update(&mut self.stats);
+        }
+    }
+}
+
+impl<K, T, P> Cache<K, Arc<T>, P>
+where
+    K: Eq + Hash + Clone,
+    T: ?Sized,
+    P: EvictionPolicy<K>,
+{
+    /// Retrieves a shared handle to a value stored behind an `Arc`.
+    ///
+    /// Caching `Arc<T>` values lets several keys share one large value, and lets readers keep a
+    /// value after the cache has evicted or replaced it, without deep-cloning it on every read.
+    /// `T` may be unsized, such as `str` or `[u8]`. The same works with `Rc` through
+    /// `get(key).cloned()` when the cache isn't shared between threads.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a new reference to the shared value, or `None` if no value is
+    /// found.
+    pub fn get_arc(&mut self, key: &K) -> Option<Arc<T>> {
+        self.get(key).cloned()
+    }
+}
+
+impl<K, P> Cache<K, Box<dyn Any>, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Retrieves a value of a particular type from a cache holding values of several types.
+    ///
+    /// Values of different concrete types can share one cache by storing them as `Box<dyn Any>`;
+    /// this looks a value up like `get` and downcasts it. When the set of types is fixed, an enum
+    /// of them works as well and avoids the boxing.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if no value is found or it isn't a `T`.
+    pub fn get_as<T: Any>(&mut self, key: &K) -> Option<&T> {
+        self.get(key)?.downcast_ref()
+    }
+}
+
+/// Copies the entries, eviction policy state, settings and stats, so the copy evicts exactly
+/// as the original would.
+///
+/// The weigher is shared with the copy, so it keeps the same weight bound. The observer, eviction
+/// callbacks, loader and writer are not copied, since sharing them between two caches would
+/// report or write the same entries twice; the copy starts without them.
+///
+/// # Panics
+/// Panics if the cache's clock doesn't support cloning (see `Clock::clone_box`), or if a boxed
+/// eviction policy doesn't (see `EvictionPolicy::clone_box`).
+impl<K, V, P> Clone for Cache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    P: EvictionPolicy<K> + Clone,
+{
+    fn clone(&self) -> Self {
+        Cache {
+            store: self.store.clone(),
+            eviction_policy: self.eviction_policy.clone(),
+            capacity: self.capacity,
+            default_ttl: self.default_ttl,
+            clock: self.clock.clone_box().expect("clock does not support cloning; override Clock::clone_box"),
+            stats: self.stats,
+            stats_enabled: self.stats_enabled,
+            pending_lookups: self.pending_lookups.clone(),
+            observer: None,
+            on_evict: None,
+            on_evict_owned: None,
+            loader: None,
+            writer: None,
+            weigher: self.weigher.clone(),
+            weight: self.weight,
+            overflow: self.overflow,
+            growth: self.growth.clone(),
+            pinned: self.pinned.clone(),
+            tracking: self.tracking,
+            epoch: self.epoch,
+        }
+    }
+}
+
+/// Shows the capacity, length, policy name and up to 16 entries, in arbitrary order. Negative
+/// entries show as `None`.
+impl<K, V, P> fmt::Debug for Cache<K, V, P>
+where
+    K: Eq + Hash + Clone + fmt::Debug,
+    V: fmt::Debug,
+    P: EvictionPolicy<K>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.store.len())
+            .field("policy", &self.eviction_policy.name())
+            .field("entries", &DebugEntries(&self.store))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Formats the first `DEBUG_ENTRIES` entries of a store, eliding the rest.
+struct DebugEntries<'a, K, V>(&'a Store<K, Entry<V>>)
+where
+    K: Eq + Hash;
+
+impl<K, V> fmt::Debug for DebugEntries<'_, K, V>
+where
+    K: Eq + Hash + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        map.entries(self.0.iter().take(DEBUG_ENTRIES).map(|(key, entry)| (key, &entry.value)));
+        if self.0.len() > DEBUG_ENTRIES {
+            map.finish_non_exhaustive()
+        } else {
+            map.finish()
+        }
+    }
+}
+
+impl<K, V, P> Extend<(K, V)> for Cache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Inserts each pair through `set`, in order.
+    ///
+    /// Eviction applies as usual, so if the iterator yields more new keys than there is room for,
+    /// later pairs may evict earlier ones from the same call.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.set(key, value);
+        }
+    }
+}
+
+impl<K, V, P> IntoIterator for Cache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Consumes the cache, yielding every unexpired key-value pair in arbitrary order.
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            entries: self.store.entries.into_iter(),
+            now: self.clock.now(),
+            epoch: self.epoch,
+        }
+    }
+}
+
+/// An iterator that moves the entries out of a `Cache`.
+///
+/// Created by `Cache::into_iter`. Entries that had expired when the iterator was created are
+/// skipped.
+pub struct IntoIter<K, V> {
+    entries: hash_map::IntoIter<K, Entry<V>>,
+    now: Instant,
+    epoch: u64,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let (now, epoch) = (self.now, self.epoch);
+        self.entries
+            .by_ref()
+            .filter(|(_, entry)| !entry.is_expired(now, epoch))
+            .find_map(|(key, entry)| Some((key, entry.value?)))
+    }
+}
+
+/// A read-only view of a `Cache` that can't change its entries or eviction order.
+///
+/// Created by `Cache::view`. A view of a `Sync` cache can be shared between threads, so several
+/// threads can read through one at once.
+pub struct CacheView<'a, K, V, P = Box<dyn EvictionPolicy<K>>>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    cache: &'a Cache<K, V, P>,
+}
+
+impl<'a, K, V, P> CacheView<'a, K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Looks up a value without recording an access or updating the stats.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if no value is found, it has expired or the key
+    /// is cached as absent.
+    pub fn peek(&self, key: &K) -> Option<&'a V> {
+        self.cache.peek(key)
+    }
+
+    /// Checks whether a key holds a value, without recording an access.
+    ///
+    /// # Parameters
+    /// * `key`: The key to check.
+    ///
+    /// # Returns
+    /// `true` if the key holds an unexpired value.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.peek(key).is_some()
+    }
+
+    /// Returns the number of key-value pairs in the cache.
+    ///
+    /// # Returns
+    /// The number of entries, as reported by `Cache::len`.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Checks whether the cache holds no entries.
+    ///
+    /// # Returns
+    /// `true` if the cache is empty, as reported by `Cache::is_empty`.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns an iterator over the cached key-value pairs, in arbitrary order.
+    ///
+    /// # Returns
+    /// An iterator yielding each key with an unexpired value, together with the value.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a K, &'a V)> {
+        let now = self.cache.clock.now();
+        let epoch = self.cache.epoch;
+        self.cache
+            .store
+            .iter()
+            .filter(move |(_, entry)| !entry.is_expired(now, epoch))
+            .filter_map(|(key, entry)| Some((key, entry.value.as_ref()?)))
     }
 }
@@ -0,0 +1,177 @@
+//!
+//! clock.rs
+//!
+//! Defines the time source used to expire cache entries.
+//!
+
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use core::ops::{ Add, AddAssign, Sub };
+#[cfg(feature = "std")]
+use std::sync::{ Arc, Mutex };
+use core::time::Duration;
+
+/// A point in time, as read from a `Clock`.
+#[cfg(feature = "std")]
+pub use std::time::Instant;
+
+/// A point in time, as read from a `Clock`.
+///
+/// Without `std` there is no system clock, so an instant is simply the time elapsed since an
+/// epoch chosen by the `Clock` that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg(not(feature = "std"))]
+pub struct Instant(Duration);
+
+#[cfg(not(feature = "std"))]
+impl Instant {
+    /// Creates an instant a given time after the clock's epoch.
+    ///
+    /// # Parameters
+    /// * `since_epoch`: The time elapsed since the epoch.
+    ///
+    /// # Returns
+    /// An `Instant` instance.
+    pub const fn from_epoch(since_epoch: Duration) -> Self {
+        Instant(since_epoch)
+    }
+
+    /// Returns the time elapsed from an earlier instant to this one.
+    ///
+    /// # Parameters
+    /// * `earlier`: The earlier instant.
+    ///
+    /// # Returns
+    /// The elapsed time, or zero if `earlier` is actually later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.0.saturating_sub(earlier.0)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        Instant(self.0 + duration)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        self.0 += duration;
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+/// A source of the current time.
+///
+/// Caches read the time through a `Clock` so tests can control expiry with a `MockClock`
+/// instead of sleeping.
+pub trait Clock {
+    /// Returns the current time.
+    ///
+    /// # Returns
+    /// The current `Instant`.
+    fn now(&self) -> Instant;
+
+    /// Copies the clock, so a `Cache` reading it can be cloned.
+    ///
+    /// # Returns
+    /// `Some` with a copy for the built-in clocks, or `None` for clocks that don't override this.
+    fn clone_box(&self) -> Option<Box<dyn Clock + Send + Sync>> {
+        None
+    }
+}
+
+/// A clock that reads the system's monotonic time.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg(feature = "std")]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn Clock + Send + Sync>> {
+        Some(Box::new(*self))
+    }
+}
+
+/// A clock that only moves when advanced by hand.
+///
+/// Clones share the same time, so a test can keep one clone and hand another to the cache.
+#[derive(Debug, Clone)]
+#[cfg(feature = "std")]
+pub struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+#[cfg(feature = "std")]
+impl MockClock {
+    /// Creates a new MockClock starting at the current system time.
+    ///
+    /// # Returns
+    /// A `MockClock` instance.
+    pub fn new() -> Self {
+        MockClock {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Moves the clock forward.
+    ///
+    /// # Parameters
+    /// * `duration`: How far to advance the clock.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn Clock + Send + Sync>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+/// The clock a cache uses until another is set.
+#[cfg(feature = "std")]
+pub(crate) use SystemClock as DefaultClock;
+
+/// The clock a cache uses until another is set. It never advances, so entries don't expire.
+#[cfg(not(feature = "std"))]
+pub(crate) struct DefaultClock;
+
+#[cfg(not(feature = "std"))]
+impl Clock for DefaultClock {
+    fn now(&self) -> Instant {
+        Instant::from_epoch(Duration::ZERO)
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn Clock + Send + Sync>> {
+        Some(Box::new(DefaultClock))
+    }
+}
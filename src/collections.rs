@@ -0,0 +1,39 @@
+//!
+//! collections.rs
+//!
+//! Selects the hash map implementation for `std` and `no_std` builds.
+//!
+
+#[cfg(feature = "std")]
+pub use std::collections::{ hash_map, HashMap, HashSet };
+#[cfg(not(feature = "std"))]
+pub use hashbrown::{ hash_map, HashMap, HashSet };
+
+/// A 64-bit FNV-1a hasher, used where `no_std` builds need a hash that is stable across calls.
+#[cfg(not(feature = "std"))]
+pub struct FnvHasher(u64);
+
+#[cfg(not(feature = "std"))]
+impl FnvHasher {
+    /// Creates a new hasher with the FNV offset basis.
+    ///
+    /// # Returns
+    /// An `FnvHasher` instance.
+    pub fn new() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::hash::Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
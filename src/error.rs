@@ -0,0 +1,94 @@
+//!
+//! error.rs
+//!
+//! Defines the errors reported for invalid configurations and entries that don't fit.
+//!
+
+use core::fmt;
+
+/// An invalid configuration rejected by a checked constructor such as `Cache::try_new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    /// A cache or policy was given a capacity of zero, so it could never hold an entry.
+    ZeroCapacity,
+    /// A segmented policy was given no segments, or a segment that can't hold an entry.
+    EmptySegment,
+    /// A policy that manages its own capacity disagrees with the cache it was given to.
+    CapacityMismatch {
+        /// The capacity reported by `EvictionPolicy::capacity`.
+        policy_capacity: usize,
+        /// The capacity of the cache.
+        cache_capacity: usize,
+    },
+    /// A policy that looks at the last `k` references or positions was given a `k` of zero.
+    ZeroK,
+    /// A policy's `k` is too large for the capacity it is used with.
+    KTooLarge {
+        /// The rejected `k`.
+        k: usize,
+        /// The capacity `k` was checked against.
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::ZeroCapacity => write!(f, "capacity must be at least 1"),
+            CacheError::EmptySegment => write!(f, "every segment must hold at least one entry"),
+            CacheError::CapacityMismatch { policy_capacity, cache_capacity } => write!(
+                f,
+                "eviction policy capacity ({}) does not match cache capacity ({})",
+                policy_capacity, cache_capacity
+            ),
+            CacheError::ZeroK => write!(f, "k must be at least 1"),
+            CacheError::KTooLarge { k, capacity } => {
+                write!(f, "k ({}) must be less than the capacity ({})", k, capacity)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CacheError {}
+
+/// Why `Cache::try_set` refused an entry, handing the pair back to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertError<K, V> {
+    /// The cache is full and the eviction policy had nothing to evict to make room.
+    Full {
+        /// The key that wasn't inserted.
+        key: K,
+        /// The value that wasn't inserted.
+        value: V,
+    },
+    /// The entry alone weighs more than the cache's maximum weight, so no amount of eviction
+    /// could make room for it.
+    TooLarge {
+        /// The key that wasn't inserted.
+        key: K,
+        /// The value that wasn't inserted.
+        value: V,
+        /// The entry's weight, as reported by the cache's weigher.
+        weight: usize,
+        /// The cache's maximum weight.
+        max: usize,
+    },
+}
+
+impl<K, V> fmt::Display for InsertError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::Full { .. } => write!(f, "cache is full and nothing could be evicted to make room"),
+            InsertError::TooLarge { weight, max, .. } => {
+                write!(f, "entry weight ({}) exceeds the cache's maximum weight ({})", weight, max)
+            }
+        }
+    }
+}
+
+impl<K, V> core::error::Error for InsertError<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+}
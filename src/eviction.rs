@@ -4,17 +4,68 @@
 //! Defines eviction policies for cache management.
 //!
 
-use rand::seq::IteratorRandom;
-use rand::thread_rng;
-use std::collections::{ HashMap, hash_map::DefaultHasher, VecDeque };
-use std::hash::{ Hash, Hasher };
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
+use core::hash::{ Hash, Hasher };
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+use core::time::Duration;
+use rand::Rng;
+#[cfg(feature = "std")]
+use rand::SeedableRng;
+#[cfg(feature = "std")]
+use rand::rngs::StdRng;
+#[cfg(not(feature = "std"))]
+use rand::rngs::SmallRng;
+use crate::clock::{ Clock, DefaultClock, Instant };
+use crate::collections::{ HashMap, HashSet };
+use crate::error::CacheError;
+use crate::sketch::CountMinSketch;
+#[cfg(not(feature = "std"))]
+use crate::collections::FnvHasher as DefaultHasher;
+
+/// The generator `RandomEviction` and `SampledLru` use unless another is given.
+#[cfg(feature = "std")]
+type DefaultRng = StdRng;
+#[cfg(not(feature = "std"))]
+type DefaultRng = SmallRng;
+
+/// Per-key bookkeeping reported by `EvictionPolicy::debug_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyMetadata {
+    /// The logical time of the key's last access; larger is more recent.
+    LastAccess(usize),
+    /// The key's access count.
+    Frequency(u64),
+    /// The key's position in the policy's queue, counting from the next candidate for eviction.
+    Position(usize),
+    /// The segment or list holding the key, counting from the first one.
+    Segment(usize),
+    /// Whether the key's reference bit is set.
+    Referenced(bool),
+}
 
-pub trait EvictionPolicy<K> {
+/// Decides which keys a `Cache` evicts, by tracking the keys it is told about.
+///
+/// Policies must be `Send` and `Sync`, so a cache holding one can be moved to another thread or
+/// read from several at once.
+pub trait EvictionPolicy<K>: Send + Sync {
     /// Called when a new key is inserted into the cache
     ///
+    /// Policies that bound their own segments or windows may decide to drop a key to make room,
+    /// independently of the cache's overall capacity. Returning that key here lets the cache
+    /// remove its entry as well, so the store and the policy never disagree.
+    ///
     /// # Parameters
     /// * `key`: The key that was inserted
-    fn on_insert(&mut self, key: &K);
+    ///
+    /// # Returns
+    /// An `Option` containing a key the policy dropped and the cache must evict, or `None`.
+    fn on_insert(&mut self, key: &K) -> Option<K>;
 
     /// Called when a key is accessed
     ///
@@ -33,12 +84,281 @@ pub trait EvictionPolicy<K> {
     /// # Returns
     /// An `Option` containing the key to evict if a suitable candidate is found, or `None`.
     fn evict(&mut self) -> Option<K>;
+
+    /// Determines several keys to evict at once, such as when the cache shrinks.
+    ///
+    /// The default calls `evict` repeatedly; policies that can pick several victims more cheaply
+    /// in one pass may override it.
+    ///
+    /// # Parameters
+    /// * `n`: The number of keys to evict.
+    ///
+    /// # Returns
+    /// Up to `n` evicted keys, in eviction order. Fewer only if the policy ran out of keys.
+    fn evict_n(&mut self, n: usize) -> Vec<K> {
+        core::iter::from_fn(|| self.evict()).take(n).collect()
+    }
+
+    /// Returns the number of keys the policy is currently tracking as eviction candidates.
+    ///
+    /// # Returns
+    /// The number of tracked keys.
+    fn len(&self) -> usize;
+
+    /// Checks to see if the policy is tracking no keys.
+    ///
+    /// # Returns
+    /// `true` if `len` is zero, otherwise `false`.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks to see if the policy is tracking a key as an eviction candidate.
+    ///
+    /// # Parameters
+    /// * `key`: The key to check.
+    ///
+    /// # Returns
+    /// `true` if the key is tracked, otherwise `false`.
+    fn contains(&self, key: &K) -> bool;
+
+    /// The number of entries the policy is sized for, if it manages its own capacity.
+    ///
+    /// Policies that size internal segments or lists up front (such as `SLRU`) report their total
+    /// capacity here so that `Cache` can verify it matches its own.
+    ///
+    /// # Returns
+    /// `Some(capacity)` for self-sized policies, or `None` if the policy adapts to any capacity.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns a short name identifying the policy, for logging and metrics labels.
+    ///
+    /// # Returns
+    /// The policy's type name for the built-in policies, or `"custom"` by default.
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    /// Dumps the metadata the policy tracks for each key, for inspecting eviction decisions.
+    ///
+    /// # Returns
+    /// Each tracked key with its metadata, in arbitrary order. The default reports nothing.
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        Vec::new()
+    }
+
+    /// Lists the tracked keys in the order the policy would evict them.
+    ///
+    /// `Cache::pin` relies on this to pass over pinned keys without disturbing their state, so
+    /// policies that can rank their keys cheaply should report it.
+    ///
+    /// # Returns
+    /// The keys, next eviction candidate first. The default reports nothing.
+    fn order(&self) -> Vec<K> {
+        Vec::new()
+    }
+
+    /// Lists the keys the policy considers equally good victims, so another policy can break the
+    /// tie, as `Composite` does.
+    ///
+    /// # Returns
+    /// The tracked keys sharing the lowest priority, in arbitrary order. The default reports only
+    /// the next victim according to `order`, which is nothing for policies that don't override
+    /// either.
+    fn victim_candidates(&self) -> Vec<K> {
+        self.order().into_iter().take(1).collect()
+    }
+
+    /// Estimates how valuable the policy considers a key, comparable across policies.
+    ///
+    /// # Parameters
+    /// * `key`: The key to score.
+    ///
+    /// # Returns
+    /// A score from 0 (next in line for eviction) to 1 (most worth keeping), or `None` if the key
+    /// isn't tracked or the policy doesn't score keys, which is the default.
+    fn hotness(&self, key: &K) -> Option<f64> {
+        let _ = key;
+        None
+    }
+
+    /// Gives up keys the policy no longer considers worth keeping, regardless of capacity.
+    ///
+    /// `Cache::sweep` calls this and removes the returned keys' entries; the policy must already
+    /// have stopped tracking them. The default gives up nothing.
+    ///
+    /// # Returns
+    /// The keys to remove from the cache.
+    fn sweep(&mut self) -> Vec<K> {
+        Vec::new()
+    }
+
+    /// Forgets every tracked key, keeping allocated collections for reuse.
+    ///
+    /// `Cache::clear` calls this after removing its entries. The default does nothing.
+    fn reset(&mut self) {}
+
+    /// Allocates room up front for at least `additional` more tracked keys.
+    ///
+    /// `Cache::new` calls this with the cache's capacity, so the policy's collections don't have
+    /// to grow, and rehash, while the cache fills. The default does nothing.
+    ///
+    /// # Parameters
+    /// * `additional`: The number of keys to make room for.
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
+    /// Releases spare capacity held by the policy's internal collections.
+    ///
+    /// `Cache::shrink_to_fit` calls this after shrinking its own store. The default does nothing.
+    fn shrink_to_fit(&mut self) {}
+
+    /// Copies the policy together with everything it tracks, so a `Cache` using it can be cloned.
+    ///
+    /// # Returns
+    /// `Some` with an independent copy for the built-in policies, or `None` for policies that
+    /// don't override this.
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        None
+    }
+
+    /// Exposes the concrete policy so a boxed policy can be downcast back to its type.
+    ///
+    /// # Returns
+    /// `Some` with the policy as `Any` for the built-in policies, or `None` for policies that don't
+    /// override this.
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        None
+    }
+}
+
+impl<K, P> EvictionPolicy<K> for Box<P>
+where
+    P: EvictionPolicy<K> + ?Sized,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        (**self).on_insert(key)
+    }
+
+    fn on_access(&mut self, key: &K) {
+        (**self).on_access(key)
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        (**self).on_remove(key)
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        (**self).evict()
+    }
+
+    fn evict_n(&mut self, n: usize) -> Vec<K> {
+        (**self).evict_n(n)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        (**self).contains(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        (**self).capacity()
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        (**self).debug_state()
+    }
+
+    fn order(&self) -> Vec<K> {
+        (**self).order()
+    }
+
+    fn victim_candidates(&self) -> Vec<K> {
+        (**self).victim_candidates()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        (**self).hotness(key)
+    }
+
+    fn sweep(&mut self) -> Vec<K> {
+        (**self).sweep()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        (**self).shrink_to_fit()
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        (**self).clone_box()
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        (**self).as_any()
+    }
+}
+
+/// Clones a boxed policy through `EvictionPolicy::clone_box`, so `Cache<K, V>` can be cloned.
+///
+/// # Panics
+/// Panics if the policy doesn't support cloning, i.e. `clone_box` returns `None`.
+impl<K> Clone for Box<dyn EvictionPolicy<K>>
+where
+    K: 'static,
+{
+    fn clone(&self) -> Self {
+        self.clone_box().expect("eviction policy does not support cloning; override EvictionPolicy::clone_box")
+    }
+}
+
+/// Scales a key's rank among `len` keys, counted from the next eviction candidate, to 0..=1.
+fn rank_hotness(rank: usize, len: usize) -> f64 {
+    if len <= 1 {
+        1.0
+    } else {
+        rank as f64 / (len - 1) as f64
+    }
 }
 
 // ==============================================================================================
 //                                      LRU Eviction Policy
 // ==============================================================================================
 
+#[derive(Clone, Debug)]
 pub struct LRU<K>
     where
         K: Eq + Hash + Copy,
@@ -62,6 +382,38 @@ impl<K> LRU<K>
         }
     }
 
+    /// Returns the number of keys the policy tracks.
+    ///
+    /// # Returns
+    /// The number of tracked keys.
+    pub fn len(&self) -> usize {
+        self.use_order.len()
+    }
+
+    /// Returns whether the policy tracks no keys.
+    ///
+    /// # Returns
+    /// `true` if no key is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.use_order.is_empty()
+    }
+
+    /// Checks whether the policy tracks a key.
+    ///
+    /// # Parameters
+    /// * `key`: The key to look for.
+    ///
+    /// # Returns
+    /// `true` if the key is tracked.
+    pub fn contains(&self, key: &K) -> bool {
+        self.use_order.contains_key(key)
+    }
+
+    /// Returns the least recently used key without evicting it.
+    fn least_recent(&self) -> Option<K> {
+        self.use_order.iter().min_by_key(|entry| entry.1).map(|(&key, _)| key)
+    }
+
     /// Resets the current time and adjusts timestamps in `use_order` to handle overflow.
     fn handle_overflow(&mut self) {
         let min_time = *self.use_order.values().min().unwrap_or(&0);
@@ -72,16 +424,26 @@ impl<K> LRU<K>
     }
 }
 
-impl<K> EvictionPolicy<K> for LRU<K>
+impl<K> Default for LRU<K>
     where
         K: Eq + Hash + Copy,
 {
-    fn on_insert(&mut self, key: &K) {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for LRU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
         self.current_time += 1;
         if self.current_time == usize::MAX {
             self.handle_overflow();
         }
         self.use_order.insert(*key, self.current_time);
+        None
     }
 
     fn on_access(&mut self, key: &K) {
@@ -106,17 +468,90 @@ impl<K> EvictionPolicy<K> for LRU<K>
             None
         }
     }
+
+    fn evict_n(&mut self, n: usize) -> Vec<K> {
+        // One sort finds every victim, rather than a full scan per key
+        let mut by_age: Vec<(usize, K)> = self.use_order.iter().map(|(&key, &time)| (time, key)).collect();
+        by_age.sort_unstable_by_key(|&(time, _)| time);
+        by_age.truncate(n);
+        for (_, key) in &by_age {
+            self.use_order.remove(key);
+        }
+        by_age.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn len(&self) -> usize {
+        LRU::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        LRU::contains(self, key)
+    }
+
+    fn name(&self) -> &'static str {
+        "LRU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.use_order.iter().map(|(&key, &time)| (key, PolicyMetadata::LastAccess(time))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        let mut by_time: Vec<(usize, K)> = self.use_order.iter().map(|(&key, &time)| (time, key)).collect();
+        by_time.sort_unstable_by_key(|&(time, _)| time);
+        by_time.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn victim_candidates(&self) -> Vec<K> {
+        self.least_recent().into_iter().collect()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        let time = *self.use_order.get(key)?;
+        let rank = self.use_order.values().filter(|&&other| other < time).count();
+        Some(rank_hotness(rank, self.use_order.len()))
+    }
+
+    fn reset(&mut self) {
+        self.use_order.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.use_order.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.use_order.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
 }
 
 // ==============================================================================================
 //                                     FIFO Eviction Policy
 // ==============================================================================================
 
+#[derive(Clone, Debug)]
 pub struct FIFO<K>
     where
         K: Eq + Hash + Copy,
 {
     queue: VecDeque<K>,
+    /// The keys in `queue`, so membership checks don't scan it.
+    members: HashSet<K>,
 }
 
 impl<K> FIFO<K>
@@ -130,16 +565,30 @@ impl<K> FIFO<K>
     pub fn new() -> Self {
         FIFO {
             queue: VecDeque::new(),
+            members: HashSet::new(),
         }
     }
 }
 
-impl<K> EvictionPolicy<K> for FIFO<K>
+impl<K> Default for FIFO<K>
     where
         K: Eq + Hash + Copy,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.queue.push_back(*key);
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for FIFO<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        // An update of a tracked key keeps its place in the queue.
+        if self.members.insert(*key) {
+            self.queue.push_back(*key);
+        }
+        None
     }
 
     fn on_access(&mut self, _key: &K) {
@@ -147,13 +596,67 @@ impl<K> EvictionPolicy<K> for FIFO<K>
     }
 
     fn on_remove(&mut self, key: &K) {
+        if !self.members.remove(key) {
+            return;
+        }
         if let Some(pos) = self.queue.iter().position(|x| x == key) {
             self.queue.remove(pos);
         }
     }
 
     fn evict(&mut self) -> Option<K> {
-        self.queue.pop_front()
+        let key = self.queue.pop_front()?;
+        self.members.remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.members.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "FIFO"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.queue.iter().enumerate().map(|(position, &key)| (key, PolicyMetadata::Position(position))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        self.queue.iter().copied().collect()
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+        self.members.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.queue.reserve(additional);
+        self.members.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+        self.members.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
@@ -162,11 +665,17 @@ impl<K> EvictionPolicy<K> for FIFO<K>
 // ==============================================================================================
 
 /// Least Frequently Used
+///
+/// Ties between keys with the same access count are broken by evicting the least recently used
+/// one, so the eviction order doesn't depend on hash map iteration order.
+#[derive(Clone, Debug)]
 pub struct LFU<K>
 where
     K: Eq + Hash + Copy,
 {
-    frequency: HashMap<K, usize>
+    /// The access count and last use time of each key.
+    frequency: HashMap<K, (usize, usize)>,
+    current_time: usize,
 }
 
 impl<K> LFU<K>
@@ -179,22 +688,36 @@ impl<K> LFU<K>
     /// An `LFU` instance.
     pub fn new() -> Self {
         LFU {
-            frequency: HashMap::new()
+            frequency: HashMap::new(),
+            current_time: 0,
         }
     }
 }
 
-impl<K> EvictionPolicy<K> for LFU<K>
+impl<K> Default for LFU<K>
     where
         K: Eq + Hash + Copy,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.frequency.insert(key.clone(), 1);
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for LFU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.current_time += 1;
+        self.frequency.insert(*key, (1, self.current_time));
+        None
     }
 
     fn on_access(&mut self, key: &K) {
-        if let Some(count) = self.frequency.get_mut(key) {
-            *count += 1
+        self.current_time += 1;
+        if let Some((count, last_used)) = self.frequency.get_mut(key) {
+            *count += 1;
+            *last_used = self.current_time;
         }
     }
 
@@ -210,30 +733,413 @@ impl<K> EvictionPolicy<K> for LFU<K>
             None
         }
     }
-}
 
-// ==============================================================================================
-//                                      MRU Eviction Policy
-// ==============================================================================================
+    fn len(&self) -> usize {
+        self.frequency.len()
+    }
 
-/// Most Recently Used
-pub struct MRU<K>
-where
-    K: Eq + Hash + Copy,
-{
-    use_order: HashMap<K, usize>,
-    current_time: usize,
-}
+    fn contains(&self, key: &K) -> bool {
+        self.frequency.contains_key(key)
+    }
 
-impl<K> MRU<K>
-where
-    K: Eq + Hash + Copy,
-{
-    /// Creates a new eviction policy instance
-    ///
-    /// # Returns
-    /// An `MRU` instance
-    pub fn new() -> Self {
+    fn name(&self) -> &'static str {
+        "LFU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.frequency.iter().map(|(&key, &(count, _))| (key, PolicyMetadata::Frequency(count as u64))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        let mut by_use: Vec<((usize, usize), K)> = self.frequency.iter().map(|(&key, &usage)| (usage, key)).collect();
+        by_use.sort_unstable_by_key(|&(usage, _)| usage);
+        by_use.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn victim_candidates(&self) -> Vec<K> {
+        let Some(min_count) = self.frequency.values().map(|&(count, _)| count).min() else {
+            return Vec::new();
+        };
+        self.frequency.iter().filter(|(_, &(count, _))| count == min_count).map(|(&key, _)| key).collect()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        let (count, _) = *self.frequency.get(key)?;
+        let max_count = self.frequency.values().map(|&(count, _)| count).max().unwrap_or(count);
+        Some(count as f64 / max_count as f64)
+    }
+
+    fn reset(&mut self) {
+        self.frequency.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.frequency.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.frequency.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  Decaying LFU Eviction Policy
+// ==============================================================================================
+
+#[cfg(feature = "std")]
+/// Least Frequently Used with frequencies that age over time
+///
+/// Every time `decay_interval` elapses, all access counts are halved, so a key that was hot long
+/// ago gradually becomes as evictable as one that was never accessed. Decay is applied lazily
+/// whenever the policy is used. As with `LFU`, ties are broken by evicting the least recently
+/// used key.
+pub struct DecayingLFU<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// The access count and last use time of each key.
+    frequency: HashMap<K, (u64, usize)>,
+    current_time: usize,
+    decay_interval: Duration,
+    clock: Box<dyn Clock + Send + Sync>,
+    last_decay: Instant,
+}
+
+#[cfg(feature = "std")]
+impl<K> DecayingLFU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new DecayingLFU eviction policy instance that reads the system clock.
+    ///
+    /// # Parameters
+    /// * `decay_interval`: How often all access counts are halved.
+    ///
+    /// # Returns
+    /// A `DecayingLFU` instance.
+    ///
+    /// # Panics
+    /// Panics if `decay_interval` is zero.
+    pub fn new(decay_interval: Duration) -> Self {
+        Self::with_clock(decay_interval, Box::new(DefaultClock))
+    }
+
+    /// Creates a new DecayingLFU eviction policy instance that reads the given clock.
+    ///
+    /// # Parameters
+    /// * `decay_interval`: How often all access counts are halved.
+    /// * `clock`: The time source, such as a `MockClock` in tests.
+    ///
+    /// # Returns
+    /// A `DecayingLFU` instance.
+    ///
+    /// # Panics
+    /// Panics if `decay_interval` is zero.
+    pub fn with_clock(decay_interval: Duration, clock: Box<dyn Clock + Send + Sync>) -> Self {
+        assert!(!decay_interval.is_zero(), "decay interval must be non-zero");
+        let last_decay = clock.now();
+        DecayingLFU {
+            frequency: HashMap::new(),
+            current_time: 0,
+            decay_interval,
+            clock,
+            last_decay,
+        }
+    }
+
+    /// Halves every access count once for each interval that has elapsed since the last decay.
+    fn decay(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now - self.last_decay;
+        let periods = elapsed.as_nanos() / self.decay_interval.as_nanos();
+        if periods == 0 {
+            return;
+        }
+        let shift = periods.min(u64::BITS as u128 - 1) as u32;
+        for (count, _) in self.frequency.values_mut() {
+            *count >>= shift;
+        }
+        self.last_decay = now;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K> fmt::Debug for DecayingLFU<K>
+    where
+        K: Eq + Hash + Copy + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecayingLFU")
+            .field("frequency", &self.frequency)
+            .field("current_time", &self.current_time)
+            .field("decay_interval", &self.decay_interval)
+            .field("last_decay", &self.last_decay)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K> EvictionPolicy<K> for DecayingLFU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.decay();
+        self.current_time += 1;
+        self.frequency.insert(*key, (1, self.current_time));
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.decay();
+        self.current_time += 1;
+        if let Some((count, last_used)) = self.frequency.get_mut(key) {
+            *count += 1;
+            *last_used = self.current_time;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.frequency.remove(key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        self.decay();
+        if let Some((&key, _)) = self.frequency.iter().min_by_key(|entry| entry.1) {
+            self.frequency.remove(&key);
+            Some(key)
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.frequency.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.frequency.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "DecayingLFU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.frequency.iter().map(|(&key, &(count, _))| (key, PolicyMetadata::Frequency(count))).collect()
+    }
+
+    fn reset(&mut self) {
+        self.frequency.clear();
+        self.current_time = 0;
+        self.last_decay = self.clock.now();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.frequency.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.frequency.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(DecayingLFU {
+            frequency: self.frequency.clone(),
+            current_time: self.current_time,
+            decay_interval: self.decay_interval,
+            clock: self.clock.clone_box()?,
+            last_decay: self.last_decay,
+        }))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                     LFUDA Eviction Policy
+// ==============================================================================================
+
+/// Least Frequently Used with Dynamic Aging
+///
+/// Each key has a priority of the cache age plus its access count, and the key with the lowest
+/// priority is evicted. The age is raised to the priority of every evicted key, so keys inserted
+/// or accessed later start out ahead of counts earned long ago, and a key that was hot once but
+/// is no longer used eventually falls behind and is evicted. Ties are broken by evicting the least
+/// recently used key.
+#[derive(Clone, Debug)]
+pub struct LFUDA<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// The access count, priority and last use time of each key.
+    entries: HashMap<K, (u64, u64, usize)>,
+    /// The priority of the most recently evicted key.
+    age: u64,
+    current_time: usize,
+}
+
+impl<K> LFUDA<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new LFUDA eviction policy instance.
+    ///
+    /// # Returns
+    /// An `LFUDA` instance.
+    pub fn new() -> Self {
+        LFUDA {
+            entries: HashMap::new(),
+            age: 0,
+            current_time: 0,
+        }
+    }
+}
+
+impl<K> Default for LFUDA<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for LFUDA<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.current_time += 1;
+        self.entries.insert(*key, (1, self.age + 1, self.current_time));
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.current_time += 1;
+        if let Some((count, priority, last_used)) = self.entries.get_mut(key) {
+            *count += 1;
+            *priority = self.age + *count;
+            *last_used = self.current_time;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        let (&key, &(_, priority, _)) = self.entries.iter().min_by_key(|(_, &(_, priority, last_used))| (priority, last_used))?;
+        self.entries.remove(&key);
+        self.age = priority;
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "LFUDA"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.entries.iter().map(|(&key, &(count, _, _))| (key, PolicyMetadata::Frequency(count))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        let mut by_priority: Vec<((u64, usize), K)> =
+            self.entries.iter().map(|(&key, &(_, priority, last_used))| ((priority, last_used), key)).collect();
+        by_priority.sort_unstable_by_key(|&(rank, _)| rank);
+        by_priority.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn victim_candidates(&self) -> Vec<K> {
+        let Some(min_priority) = self.entries.values().map(|&(_, priority, _)| priority).min() else {
+            return Vec::new();
+        };
+        self.entries.iter().filter(|(_, &(_, priority, _))| priority == min_priority).map(|(&key, _)| key).collect()
+    }
+
+    fn reset(&mut self) {
+        self.entries.clear();
+        self.age = 0;
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                      MRU Eviction Policy
+// ==============================================================================================
+
+/// Most Recently Used
+#[derive(Clone, Debug)]
+pub struct MRU<K>
+where
+    K: Eq + Hash + Copy,
+{
+    use_order: HashMap<K, usize>,
+    current_time: usize,
+}
+
+impl<K> MRU<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// Creates a new eviction policy instance
+    ///
+    /// # Returns
+    /// An `MRU` instance
+    pub fn new() -> Self {
         MRU {
             use_order: HashMap::new(),
             current_time: 0,
@@ -241,473 +1147,2856 @@ where
     }
 }
 
-impl<K> EvictionPolicy<K> for MRU<K>
-where
-    K: Eq + Hash + Copy,
+impl<K> Default for MRU<K>
+where
+    K: Eq + Hash + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for MRU<K>
+where
+    K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.current_time += 1;
+        self.use_order.insert(*key, self.current_time);
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.current_time += 1;
+        self.use_order.insert(*key, self.current_time);
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.use_order.remove(key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        if let Some((&key, _)) = self.use_order.iter().max_by_key(|entry| entry.1) {
+            self.use_order.remove(&key);
+            Some(key)
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.use_order.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.use_order.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "MRU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.use_order.iter().map(|(&key, &time)| (key, PolicyMetadata::LastAccess(time))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        let mut by_time: Vec<(usize, K)> = self.use_order.iter().map(|(&key, &time)| (time, key)).collect();
+        by_time.sort_unstable_by_key(|&(time, _)| core::cmp::Reverse(time));
+        by_time.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn reset(&mut self) {
+        self.use_order.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.use_order.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.use_order.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                     Random Eviction Policy
+// ==============================================================================================
+
+/// Random Eviction Policy
+///
+/// Victims are drawn from `R`, which defaults to a `StdRng` seeded from the thread-local
+/// generator; the thread-local generator itself can't be sent to another thread with the cache.
+/// Passing a seeded generator to `with_rng` makes the eviction order reproducible.
+#[derive(Clone, Debug)]
+pub struct RandomEviction<K, R = DefaultRng>
+where
+    K: Eq + Hash + Copy,
+    R: Rng,
+{
+    keys: Vec<K>,
+    positions: HashMap<K, usize>,
+    rng: R,
+}
+
+#[cfg(feature = "std")]
+impl<K> RandomEviction<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// Creates a new Random Eviction policy instance.
+    ///
+    /// # Returns
+    /// A `RandomEviction` instance.
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_rng(&mut rand::rng()))
+    }
+}
+
+impl<K, R> RandomEviction<K, R>
+where
+    K: Eq + Hash + Copy,
+    R: Rng,
+{
+    /// Creates a new Random Eviction policy instance that draws victims from the given generator.
+    ///
+    /// # Parameters
+    /// * `rng`: The random number generator, such as a seeded `StdRng`.
+    ///
+    /// # Returns
+    /// A `RandomEviction` instance.
+    pub fn with_rng(rng: R) -> Self {
+        RandomEviction {
+            keys: Vec::new(),
+            positions: HashMap::new(),
+            rng,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K> Default for RandomEviction<K>
+where
+    K: Eq + Hash + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, R> EvictionPolicy<K> for RandomEviction<K, R>
+where
+    K: Eq + Hash + Copy + Send + Sync,
+    R: Rng + Clone + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        if !self.positions.contains_key(key) {
+            self.positions.insert(*key, self.keys.len());
+            self.keys.push(*key);
+        }
+        None
+    }
+
+    fn on_access(&mut self, _key: &K) {
+        // Do nothing on access
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(position) = self.positions.remove(key) {
+            self.keys.swap_remove(position);
+            if let Some(moved) = self.keys.get(position) {
+                self.positions.insert(*moved, position);
+            }
+        }
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let key = self.keys[self.rng.random_range(0..self.keys.len())];
+        self.on_remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "RandomEviction"
+    }
+
+    fn reset(&mut self) {
+        self.keys.clear();
+        self.positions.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.positions.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.positions.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  Sampled LRU Eviction Policy
+// ==============================================================================================
+
+/// Sampled (Approximate) LRU Eviction Policy
+///
+/// Rather than keeping every key in recency order, each key only carries a coarse 32-bit
+/// last-access stamp. `evict` draws `sample_size` keys at random, with replacement, and evicts the
+/// least recently used of them. This makes eviction cost independent of the cache size, for very
+/// large caches where exact LRU bookkeeping is too expensive, at the cost of sometimes evicting a
+/// key that isn't the oldest. Larger samples approximate LRU more closely.
+///
+/// Stamps wrap around after 2^32 accesses; ages are measured modulo that, so only keys left
+/// untouched for that long can be misjudged.
+#[derive(Clone, Debug)]
+pub struct SampledLru<K, R = DefaultRng>
+where
+    K: Eq + Hash + Copy,
+    R: Rng,
+{
+    keys: Vec<K>,
+    /// Each key's index in `keys` and its last-access stamp.
+    entries: HashMap<K, (usize, u32)>,
+    sample_size: usize,
+    current_time: u32,
+    rng: R,
+}
+
+#[cfg(feature = "std")]
+impl<K> SampledLru<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// Creates a new Sampled LRU eviction policy instance.
+    ///
+    /// # Parameters
+    /// * `sample_size`: How many keys each eviction compares.
+    ///
+    /// # Returns
+    /// A `SampledLru` instance.
+    ///
+    /// # Panics
+    /// Panics if `sample_size` is zero.
+    pub fn new(sample_size: usize) -> Self {
+        Self::with_rng(sample_size, StdRng::from_rng(&mut rand::rng()))
+    }
+}
+
+impl<K, R> SampledLru<K, R>
+where
+    K: Eq + Hash + Copy,
+    R: Rng,
+{
+    /// Creates a new Sampled LRU eviction policy instance that samples with the given generator.
+    ///
+    /// # Parameters
+    /// * `sample_size`: How many keys each eviction compares.
+    /// * `rng`: The random number generator, such as a seeded `StdRng`.
+    ///
+    /// # Returns
+    /// A `SampledLru` instance.
+    ///
+    /// # Panics
+    /// Panics if `sample_size` is zero.
+    pub fn with_rng(sample_size: usize, rng: R) -> Self {
+        assert!(sample_size > 0, "sample size must be at least 1");
+        SampledLru {
+            keys: Vec::new(),
+            entries: HashMap::new(),
+            sample_size,
+            current_time: 0,
+            rng,
+        }
+    }
+
+    /// Advances the clock, returning the new stamp.
+    fn tick(&mut self) -> u32 {
+        self.current_time = self.current_time.wrapping_add(1);
+        self.current_time
+    }
+
+    /// Returns how many ticks ago a stamp was taken.
+    fn age(&self, stamp: u32) -> u32 {
+        self.current_time.wrapping_sub(stamp)
+    }
+}
+
+impl<K, R> EvictionPolicy<K> for SampledLru<K, R>
+where
+    K: Eq + Hash + Copy + Send + Sync,
+    R: Rng + Clone + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        let stamp = self.tick();
+        match self.entries.get_mut(key) {
+            Some((_, last_used)) => *last_used = stamp,
+            None => {
+                self.entries.insert(*key, (self.keys.len(), stamp));
+                self.keys.push(*key);
+            }
+        }
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        let stamp = self.tick();
+        if let Some((_, last_used)) = self.entries.get_mut(key) {
+            *last_used = stamp;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some((position, _)) = self.entries.remove(key) {
+            self.keys.swap_remove(position);
+            if let Some(moved) = self.keys.get(position) {
+                if let Some(entry) = self.entries.get_mut(moved) {
+                    entry.0 = position;
+                }
+            }
+        }
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let mut victim = None;
+        let mut oldest = 0;
+        for _ in 0..self.sample_size {
+            let key = self.keys[self.rng.random_range(0..self.keys.len())];
+            let age = self.age(self.entries[&key].1);
+            if victim.is_none() || age > oldest {
+                victim = Some(key);
+                oldest = age;
+            }
+        }
+        let key = victim?;
+        self.on_remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "SampledLru"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.entries.iter().map(|(&key, &(_, stamp))| (key, PolicyMetadata::LastAccess(stamp as usize))).collect()
+    }
+
+    fn reset(&mut self) {
+        self.keys.clear();
+        self.entries.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.entries.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.entries.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                     SLRU Eviction Policy
+// ==============================================================================================
+
+/// Segmented Least Recently Used
+///
+/// The probationary and protected segments together must hold exactly as many entries as the
+/// `Cache` they are used with, i.e. `probationary_capacity + protected_capacity == capacity`.
+/// `Cache::new` panics if the two disagree, since a smaller policy would drop keys while the
+/// store still has room and a larger one would never be asked to evict them.
+#[derive(Clone, Debug)]
+pub struct SLRU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    probationary: LRU<K>,
+    protected: LRU<K>,
+    probationary_capacity: usize,
+    protected_capacity: usize,
+}
+
+impl<K> SLRU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new SLRU eviction policy instance
+    ///
+    /// # Parameters
+    /// * `probationary_capacity`: The capacity of the probationary segment.
+    /// * `protected_capacity`: The capacity of the protected segment.
+    ///
+    /// # Returns
+    /// An `SLRU` instance
+    ///
+    /// # Panics
+    /// Panics if either segment has a capacity of zero.
+    pub fn new(probationary_capacity: usize, protected_capacity: usize) -> Self {
+        Self::try_new(probationary_capacity, protected_capacity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new SLRU eviction policy instance, rejecting empty segments instead of panicking.
+    ///
+    /// # Parameters
+    /// * `probationary_capacity`: The capacity of the probationary segment.
+    /// * `protected_capacity`: The capacity of the protected segment.
+    ///
+    /// # Returns
+    /// An `SLRU` instance, or `CacheError::EmptySegment` if either segment has a capacity of zero.
+    pub fn try_new(probationary_capacity: usize, protected_capacity: usize) -> Result<Self, CacheError> {
+        if probationary_capacity == 0 || protected_capacity == 0 {
+            return Err(CacheError::EmptySegment);
+        }
+        Ok(SLRU {
+            probationary: LRU::new(),
+            protected: LRU::new(),
+            probationary_capacity,
+            protected_capacity,
+        })
+    }
+
+    /// Returns the combined capacity of the probationary and protected segments.
+    ///
+    /// # Returns
+    /// The total number of keys the policy tracks before it starts dropping probationary keys.
+    pub fn capacity(&self) -> usize {
+        self.probationary_capacity + self.protected_capacity
+    }
+
+    fn move_to_protected(&mut self, key: &K)
+    where
+        K: Send + Sync,
+    {
+        if self.probationary.contains(key) {
+            self.probationary.on_remove(key);
+            if self.protected.len() >= self.protected_capacity {
+                // Demote the least recently used protected key rather than forgetting it.
+                if let Some(demoted_key) = self.protected.evict() {
+                    self.probationary.on_insert(&demoted_key);
+                }
+            }
+            self.protected.on_insert(key);
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for SLRU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        // An update of a tracked key keeps its current segment.
+        if self.contains(key) {
+            return None;
+        }
+        // The probationary segment may borrow room the protected segment isn't using, so only drop
+        // a key once the policy as a whole is full.
+        let dropped_key = if self.len() >= self.capacity() {
+            self.evict()
+        } else {
+            None
+        };
+        self.probationary.on_insert(key);
+        dropped_key
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if self.probationary.contains(key) {
+            self.move_to_protected(key);
+        } else {
+            self.protected.on_access(key);
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if self.probationary.contains(key) {
+            self.probationary.on_remove(key);
+        } else {
+            self.protected.on_remove(key);
+        }
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        if let Some(evicted_key) = self.probationary.evict() {
+            return Some(evicted_key);
+        }
+        self.protected.evict()
+    }
+
+    fn len(&self) -> usize {
+        self.probationary.len() + self.protected.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.probationary.contains(key) || self.protected.contains(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(SLRU::capacity(self))
+    }
+
+    fn name(&self) -> &'static str {
+        "SLRU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        let probationary = self.probationary.debug_state().into_iter().map(|(key, _)| (key, PolicyMetadata::Segment(0)));
+        let protected = self.protected.debug_state().into_iter().map(|(key, _)| (key, PolicyMetadata::Segment(1)));
+        probationary.chain(protected).collect()
+    }
+
+    fn reset(&mut self) {
+        self.probationary.reset();
+        self.protected.reset();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Reserving for the whole cache in every segment would overallocate
+        self.probationary.reserve(additional.min(self.probationary_capacity));
+        self.protected.reserve(additional.min(self.protected_capacity));
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.probationary.shrink_to_fit();
+        self.protected.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  Ring FIFO Eviction Policy
+// ==============================================================================================
+
+/// First In First Out over a fixed-size ring buffer
+///
+/// Evicts in the same order as `FIFO`, but the queue is a ring of slots allocated once at the
+/// cache's capacity, and a map from each key to its slot makes `contains` and `on_remove`
+/// constant time. Removing a key leaves an empty slot behind, which is skipped when it reaches the
+/// head. If empty slots fill the ring, the live keys are compacted in place. The capacity must
+/// match that of the `Cache` the policy is used with.
+#[derive(Clone, Debug)]
+pub struct RingFifo<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    slots: Box<[Option<K>]>,
+    /// The slot holding the oldest key.
+    head: usize,
+    /// The number of slots from `head` on that are in use, including emptied ones.
+    used: usize,
+    positions: HashMap<K, usize>,
+}
+
+impl<K> RingFifo<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new Ring FIFO eviction policy instance.
+    ///
+    /// # Parameters
+    /// * `capacity`: The number of keys the ring holds, which must match the cache's capacity.
+    ///
+    /// # Returns
+    /// A `RingFifo` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "ring capacity must be at least 1");
+        RingFifo {
+            slots: vec![None; capacity].into_boxed_slice(),
+            head: 0,
+            used: 0,
+            positions: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the index of the slot `offset` places after the head.
+    fn slot(&self, offset: usize) -> usize {
+        (self.head + offset) % self.slots.len()
+    }
+
+    /// Drops emptied slots from both ends of the used range.
+    fn trim(&mut self) {
+        while self.used > 0 && self.slots[self.head].is_none() {
+            self.head = self.slot(1);
+            self.used -= 1;
+        }
+        while self.used > 0 && self.slots[self.slot(self.used - 1)].is_none() {
+            self.used -= 1;
+        }
+    }
+
+    /// Moves the live keys towards the head, in order, so the emptied slots between them are
+    /// freed at the tail.
+    fn compact(&mut self) {
+        let mut live = 0;
+        for offset in 0..self.used {
+            let from = self.slot(offset);
+            if let Some(key) = self.slots[from].take() {
+                let to = self.slot(live);
+                self.slots[to] = Some(key);
+                self.positions.insert(key, to);
+                live += 1;
+            }
+        }
+        self.used = live;
+    }
+}
+
+impl<K> EvictionPolicy<K> for RingFifo<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        // An update of a tracked key keeps its place in the queue.
+        if self.positions.contains_key(key) {
+            return None;
+        }
+        let mut dropped = None;
+        if self.used == self.slots.len() {
+            self.compact();
+            if self.used == self.slots.len() {
+                dropped = self.evict();
+            }
+        }
+        let slot = self.slot(self.used);
+        self.slots[slot] = Some(*key);
+        self.positions.insert(*key, slot);
+        self.used += 1;
+        dropped
+    }
+
+    fn on_access(&mut self, _key: &K) {
+        // Do nothing on access.
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(slot) = self.positions.remove(key) {
+            self.slots[slot] = None;
+            self.trim();
+        }
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        // The head slot is never empty, since emptied slots are trimmed as they reach it
+        let key = self.slots[self.head].take()?;
+        self.positions.remove(&key);
+        self.head = self.slot(1);
+        self.used -= 1;
+        self.trim();
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.slots.len())
+    }
+
+    fn name(&self) -> &'static str {
+        "RingFifo"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.order().into_iter().enumerate().map(|(position, key)| (key, PolicyMetadata::Position(position))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        (0..self.used).filter_map(|offset| self.slots[self.slot(offset)]).collect()
+    }
+
+    fn reset(&mut self) {
+        self.slots.fill(None);
+        self.head = 0;
+        self.used = 0;
+        self.positions.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        // The ring is sized once, so only the slot map can give memory back
+        self.positions.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  SFIFO Eviction Policy
+// ==============================================================================================
+
+/// Segmented First In First Out
+///
+/// Keys are spread across segments by hash. The effective capacity is
+/// `num_segments * segment_capacity`, which must match the capacity of the `Cache` the policy is
+/// used with. A segment may grow past `segment_capacity` while other segments have room, so an
+/// uneven key distribution doesn't cause keys to be dropped before the cache is full.
+#[derive(Clone, Debug)]
+pub struct SFIFO<K>
+where
+    K: Eq + Hash + Copy,
+{
+    segments: Vec<VecDeque<K>>,
+    /// The segment each tracked key was placed in, so a key is only hashed once.
+    assignments: HashMap<K, usize>,
+    segment_capacity: usize,
+}
+
+impl<K> SFIFO<K>
+where
+    K: Eq + Hash + Copy,
+{
+    /// Creates a new SFIFO eviction policy instance
+    ///
+    /// # Parameters
+    /// * `num_segments`: The number of segments to divide the cache into.
+    /// * `segment_capacity`: The maximum number of items each segment can hold.
+    ///
+    /// # Returns
+    /// A `SFIFO` instance.
+    ///
+    /// # Panics
+    /// Panics if `num_segments` or `segment_capacity` is zero.
+    pub fn new(num_segments: usize, segment_capacity: usize) -> Self {
+        Self::try_new(num_segments, segment_capacity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new SFIFO eviction policy instance, rejecting empty segments instead of panicking.
+    ///
+    /// # Parameters
+    /// * `num_segments`: The number of segments to divide the cache into.
+    /// * `segment_capacity`: The maximum number of items each segment can hold.
+    ///
+    /// # Returns
+    /// A `SFIFO` instance, or `CacheError::EmptySegment` if `num_segments` or `segment_capacity`
+    /// is zero.
+    pub fn try_new(num_segments: usize, segment_capacity: usize) -> Result<Self, CacheError> {
+        if num_segments == 0 || segment_capacity == 0 {
+            return Err(CacheError::EmptySegment);
+        }
+        Ok(SFIFO {
+            segments: vec![VecDeque::new(); num_segments],
+            assignments: HashMap::new(),
+            segment_capacity,
+        })
+    }
+
+    /// Determines the segment index for a given key
+    ///
+    /// # Parameters
+    /// * `key`: The key to determine the segment for.
+    ///
+    /// # Returns
+    /// The segment index
+    fn segment_index(&self, key: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() as usize) % self.segments.len()
+    }
+
+    /// Returns the effective capacity of the policy across all segments.
+    ///
+    /// # Returns
+    /// `num_segments * segment_capacity`.
+    pub fn capacity(&self) -> usize {
+        self.segments.len() * self.segment_capacity
+    }
+}
+
+impl<K> EvictionPolicy<K> for SFIFO<K>
+where
+    K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        // An update of a tracked key keeps its place in the queue.
+        if self.assignments.contains_key(key) {
+            return None;
+        }
+        let index = self.segment_index(key);
+        let dropped_key = if self.len() >= self.capacity() {
+            match self.segments[index].pop_front() {
+                Some(dropped_key) => {
+                    self.assignments.remove(&dropped_key);
+                    Some(dropped_key)
+                }
+                None => self.evict(),
+            }
+        } else {
+            None
+        };
+        self.segments[index].push_back(*key);
+        self.assignments.insert(*key, index);
+        dropped_key
+    }
+
+    fn on_access(&mut self, _key: &K) {
+        // Do nothing on access
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(index) = self.assignments.remove(key) {
+            self.segments[index].retain(|x| x != key);
+        }
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        for segment in &mut self.segments {
+            if let Some(key) = segment.pop_front() {
+                self.assignments.remove(&key);
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.assignments.contains_key(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(SFIFO::capacity(self))
+    }
+
+    fn name(&self) -> &'static str {
+        "SFIFO"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.segments
+            .iter()
+            .enumerate()
+            .flat_map(|(index, segment)| segment.iter().map(move |&key| (key, PolicyMetadata::Segment(index))))
+            .collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        self.segments.iter().flatten().copied().collect()
+    }
+
+    fn reset(&mut self) {
+        for segment in &mut self.segments {
+            segment.clear();
+        }
+        self.assignments.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.assignments.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        for segment in &mut self.segments {
+            segment.shrink_to_fit();
+        }
+        self.assignments.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  KLRU Eviction Policy
+// ==============================================================================================
+
+/// K-Largest Recently Used
+///
+/// Evicts the key with `k` more recently used keys ahead of it, so `k = 0` evicts the most
+/// recently used key. When the policy tracks `k` keys or fewer there is no such key, and it falls
+/// back to evicting the least recently used one; a `k` at or above the cache capacity therefore
+/// behaves like `LRU`.
+#[derive(Clone, Debug)]
+pub struct KLRU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    use_order: VecDeque<K>,
+    k: usize,
+}
+
+impl<K> KLRU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new KLRU eviction policy instance.
+    ///
+    /// # Parameters
+    /// * `k`: The position of the key to be evicted.
+    ///
+    /// # Returns
+    /// A `KLRU` instance.
+    pub fn new(k: usize) -> Self {
+        KLRU {
+            use_order: VecDeque::new(),
+            k,
+        }
+    }
+
+    /// Creates a new KLRU eviction policy instance for a cache of a known capacity, rejecting a
+    /// `k` that would make it behave like `LRU`.
+    ///
+    /// # Parameters
+    /// * `k`: The position of the key to be evicted.
+    /// * `capacity`: The capacity of the `Cache` the policy will be used with.
+    ///
+    /// # Returns
+    /// A `KLRU` instance, `CacheError::ZeroCapacity` if `capacity` is zero, or
+    /// `CacheError::KTooLarge` if `k` is not less than `capacity`.
+    pub fn try_new(k: usize, capacity: usize) -> Result<Self, CacheError> {
+        if capacity == 0 {
+            return Err(CacheError::ZeroCapacity);
+        }
+        if k >= capacity {
+            return Err(CacheError::KTooLarge { k, capacity });
+        }
+        Ok(Self::new(k))
+    }
+}
+
+impl<K> EvictionPolicy<K> for KLRU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.use_order.retain(|x| x != key);
+        self.use_order.push_back(*key);
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.use_order.retain(|x| x != key);
+        self.use_order.push_back(*key);
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.use_order.retain(|x| x != key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        if self.use_order.len() > self.k {
+            let evicted_key = self.use_order[self.use_order.len() - 1 - self.k];
+            self.use_order.retain(|x| x != &evicted_key);
+            Some(evicted_key)
+        } else {
+            // Too few keys to skip k of them, so fall back to least recently used
+            self.use_order.pop_front()
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.use_order.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.use_order.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "KLRU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.use_order.iter().enumerate().map(|(position, &key)| (key, PolicyMetadata::Position(position))).collect()
+    }
+
+    fn reset(&mut self) {
+        self.use_order.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.use_order.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.use_order.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  LRU-K Eviction Policy
+// ==============================================================================================
+
+/// LRU-K (O'Neil et al.)
+///
+/// Records the last `k` reference times of each key and evicts the key whose `k`-th most recent
+/// reference is oldest. Keys referenced fewer than `k` times have an infinite backward distance
+/// and are evicted first, least recently used among them first. Unlike `KLRU`, a key has to be
+/// referenced repeatedly to be protected from eviction.
+#[derive(Clone, Debug)]
+pub struct LruK<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// The last `k` reference times of each key, oldest first.
+    history: HashMap<K, VecDeque<usize>>,
+    k: usize,
+    current_time: usize,
+}
+
+impl<K> LruK<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new LruK eviction policy instance.
+    ///
+    /// # Parameters
+    /// * `k`: How many references to remember per key.
+    ///
+    /// # Returns
+    /// An `LruK` instance.
+    ///
+    /// # Panics
+    /// Panics if `k` is zero.
+    pub fn new(k: usize) -> Self {
+        Self::try_new(k).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new LruK eviction policy instance, rejecting a zero `k` instead of panicking.
+    ///
+    /// # Parameters
+    /// * `k`: How many references to remember per key.
+    ///
+    /// # Returns
+    /// An `LruK` instance, or `CacheError::ZeroK` if `k` is zero.
+    pub fn try_new(k: usize) -> Result<Self, CacheError> {
+        if k == 0 {
+            return Err(CacheError::ZeroK);
+        }
+        Ok(LruK {
+            history: HashMap::new(),
+            k,
+            current_time: 0,
+        })
+    }
+
+    /// Records a reference to a key at the current time.
+    fn reference(&mut self, key: &K) {
+        self.current_time += 1;
+        if let Some(times) = self.history.get_mut(key) {
+            if times.len() == self.k {
+                times.pop_front();
+            }
+            times.push_back(self.current_time);
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for LruK<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.history.entry(*key).or_insert_with(|| VecDeque::with_capacity(self.k));
+        self.reference(key);
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.reference(key);
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.history.remove(key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        let k = self.k;
+        // Keys with fewer than k references sort first, by their most recent reference
+        let (&key, _) = self.history.iter().min_by_key(|(_, times)| {
+            if times.len() < k {
+                (false, times.back().copied())
+            } else {
+                (true, times.front().copied())
+            }
+        })?;
+        self.history.remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.history.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "LruK"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.history
+            .iter()
+            .filter_map(|(&key, times)| Some((key, PolicyMetadata::LastAccess(*times.back()?))))
+            .collect()
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.history.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.history.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  Second-Chance Eviction Policy
+// ==============================================================================================
+
+/// Second-Chance Eviction Policy
+#[derive(Clone, Debug)]
+pub struct SecondChance<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    queue: VecDeque<(K, bool)>,
+}
+
+impl<K> SecondChance<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new Second-Chance eviction policy instance
+    ///
+    /// # Returns
+    /// A `SecondChance` instance.
+    pub fn new() -> Self {
+        SecondChance {
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<K> Default for SecondChance<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for SecondChance<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        // An update of a tracked key keeps its place in the queue and its reference bit.
+        if !self.contains(key) {
+            self.queue.push_back((*key, false));
+        }
+        None
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some((_, ref mut referenced)) = self.queue.iter_mut().find(|(k, _)| k == key) {
+            *referenced = true;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.queue.retain(|(k, _)| k != key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        // One rotation clears every reference bit, so if no unreferenced key turns up within
+        // `queue.len()` steps the front of the queue is the oldest entry and is evicted.
+        for _ in 0..self.queue.len() {
+            match self.queue.front_mut() {
+                Some((_, referenced)) if *referenced => {
+                    *referenced = false;
+                    self.queue.rotate_left(1);
+                }
+                _ => break,
+            }
+        }
+        self.queue.pop_front().map(|(key, _)| key)
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.queue.iter().any(|(k, _)| k == key)
+    }
+
+    fn name(&self) -> &'static str {
+        "SecondChance"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.queue.iter().map(|&(key, referenced)| (key, PolicyMetadata::Referenced(referenced))).collect()
+    }
+
+    fn order(&self) -> Vec<K> {
+        // Referenced keys are passed over once, so they go after every unreferenced key
+        let unreferenced = self.queue.iter().filter(|(_, referenced)| !referenced);
+        let referenced = self.queue.iter().filter(|(_, referenced)| *referenced);
+        unreferenced.chain(referenced).map(|&(key, _)| key).collect()
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.queue.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                  ARC Eviction Policy
+// ==============================================================================================
+
+/// Adaptive Replacement Cache
+#[derive(Clone, Debug)]
+pub struct ARC<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    t1: VecDeque<K>,
+    t2: VecDeque<K>,
+    b1: VecDeque<K>,
+    b2: VecDeque<K>,
+    p: usize,
+    capacity: usize,
+}
+
+impl<K> ARC<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new ARC eviction policy instance
+    ///
+    /// # Parameters
+    /// * `capacity`: The maximum number of items the cache can hold.
+    ///
+    /// # Returns
+    /// An `ARC` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self::try_new(capacity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new ARC eviction policy instance, rejecting a zero capacity instead of panicking.
+    ///
+    /// # Parameters
+    /// * `capacity`: The maximum number of items the cache can hold.
+    ///
+    /// # Returns
+    /// An `ARC` instance, or `CacheError::ZeroCapacity` if `capacity` is zero.
+    pub fn try_new(capacity: usize) -> Result<Self, CacheError> {
+        if capacity == 0 {
+            return Err(CacheError::ZeroCapacity);
+        }
+        Ok(ARC {
+            t1: VecDeque::new(),
+            t2: VecDeque::new(),
+            b1: VecDeque::new(),
+            b2: VecDeque::new(),
+            p: 0,
+            capacity,
+        })
+    }
+
+    /// Returns the number of keys in all four lists, resident and ghost.
+    ///
+    /// # Returns
+    /// The directory size, which never exceeds twice the capacity.
+    pub fn directory_len(&self) -> usize {
+        self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len()
+    }
+
+    /// Returns the adaptive target size for `t1`.
+    ///
+    /// # Returns
+    /// The number of recency-only keys ARC currently aims to keep resident.
+    pub fn target(&self) -> usize {
+        self.p
+    }
+
+    /// Handles a reference to a key in a ghost list by adapting `p` and making the key resident
+    /// in `t2`.
+    ///
+    /// # Returns
+    /// `true` if the key was a ghost, otherwise `false`.
+    fn ghost_hit(&mut self, key: &K) -> bool {
+        if let Some(position) = self.b1.iter().position(|x| x == key) {
+            let delta = core::cmp::max(self.b2.len() / self.b1.len(), 1);
+            self.p = core::cmp::min(self.capacity, self.p + delta);
+            self.b1.remove(position);
+        } else if let Some(position) = self.b2.iter().position(|x| x == key) {
+            let delta = core::cmp::max(self.b1.len() / self.b2.len(), 1);
+            self.p = self.p.saturating_sub(delta);
+            self.b2.remove(position);
+        } else {
+            return false;
+        }
+        self.t2.push_back(*key);
+        true
+    }
+
+    /// Moves the least recently used resident of `t1` or `t2` into its ghost list.
+    ///
+    /// # Returns
+    /// The demoted key, or `None` if nothing is resident.
+    fn demote(&mut self) -> Option<K> {
+        if !self.t1.is_empty() && (self.t1.len() > self.p || self.t2.is_empty()) {
+            let old = self.t1.pop_front()?;
+            self.b1.push_back(old);
+            Some(old)
+        } else {
+            let old = self.t2.pop_front()?;
+            self.b2.push_back(old);
+            Some(old)
+        }
+    }
+
+    /// Drops the oldest ghosts until `|t1| + |b1| <= c` and the directory holds at most `2c` keys.
+    fn trim_ghosts(&mut self) {
+        while self.t1.len() + self.b1.len() > self.capacity && self.b1.pop_front().is_some() {}
+        while self.directory_len() > 2 * self.capacity {
+            if self.b2.pop_front().is_none() && self.b1.pop_front().is_none() {
+                break;
+            }
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for ARC<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        if self.t1.contains(key) || self.t2.contains(key) {
+            return None;
+        }
+
+        // A key remembered as a ghost was evicted too early, so it adapts p and joins t2
+        if !self.ghost_hit(key) {
+            self.t1.push_back(*key);
+        }
+
+        // The cache evicts before inserting, so this only drops a key if the cache is larger
+        // than the capacity the policy was created with
+        let dropped = if self.t1.len() + self.t2.len() > self.capacity {
+            self.demote()
+        } else {
+            None
+        };
+        self.trim_ghosts();
+        dropped
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if self.t1.contains(key) {
+            self.t1.retain(|x| x != key);
+            self.t2.push_back(*key);
+        } else if self.t2.contains(key) {
+            self.t2.retain(|x| x != key);
+            self.t2.push_back(*key);
+        } else {
+            self.ghost_hit(key);
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        self.t1.retain(|x| x != key);
+        self.t2.retain(|x| x != key);
+        self.b1.retain(|x| x != key);
+        self.b2.retain(|x| x != key);
+    }
+
+    fn evict(&mut self) -> Option<K> {
+        let evicted = self.demote();
+        self.trim_ghosts();
+        evicted
+    }
+
+    fn len(&self) -> usize {
+        self.t1.len() + self.t2.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.t1.contains(key) || self.t2.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "ARC"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        let t1 = self.t1.iter().map(|&key| (key, PolicyMetadata::Segment(0)));
+        let t2 = self.t2.iter().map(|&key| (key, PolicyMetadata::Segment(1)));
+        t1.chain(t2).collect()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        // Keys seen once rank below keys seen again, each list ordered by recency
+        if let Some(rank) = self.t1.iter().position(|x| x == key) {
+            return Some(0.5 * rank_hotness(rank, self.t1.len()));
+        }
+        let rank = self.t2.iter().position(|x| x == key)?;
+        Some(0.5 + 0.5 * rank_hotness(rank, self.t2.len()))
+    }
+
+    fn reset(&mut self) {
+        self.t1.clear();
+        self.t2.clear();
+        self.b1.clear();
+        self.b2.clear();
+        self.p = 0;
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.t1.shrink_to_fit();
+        self.t2.shrink_to_fit();
+        self.b1.shrink_to_fit();
+        self.b2.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
+}
+
+// ==============================================================================================
+//                                W-TinyLFU Eviction Policy
+// ==============================================================================================
+
+/// Window Tiny Least Frequently Used (Einziger et al., as used by Caffeine)
+///
+/// New keys enter a small LRU admission window. Keys leaving the window become candidates for a
+/// segmented LRU main region, with probationary and protected segments like `SLRU`, and are only
+/// admitted if a `CountMinSketch` estimates they are used more often than the key the main region
+/// would evict; otherwise the candidate itself is evicted. The window lets bursts of new keys
+/// build up a frequency before competing, while the admission check keeps one-off keys from
+/// flushing out popular ones. The sketch is aged periodically so old popularity fades.
+///
+/// The window and main region together hold exactly as many entries as the `Cache` they are used
+/// with, so the policy reports its capacity through `EvictionPolicy::capacity`.
+#[derive(Clone, Debug)]
+pub struct WTinyLFU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    window: LRU<K>,
+    probationary: LRU<K>,
+    protected: LRU<K>,
+    sketch: CountMinSketch<K>,
+    window_capacity: usize,
+    main_capacity: usize,
+    protected_capacity: usize,
+    /// The number of recorded uses since the sketch was last aged.
+    samples: usize,
+    /// How many uses to record before aging the sketch.
+    sample_size: usize,
+}
+
+impl<K> WTinyLFU<K>
+    where
+        K: Eq + Hash + Copy,
+{
+    /// Creates a new WTinyLFU eviction policy instance.
+    ///
+    /// Caffeine uses a `window_ratio` of 0.01 and a `protected_ratio` of 0.8, with a sketch a few
+    /// times wider than the capacity and a depth of 4.
+    ///
+    /// # Parameters
+    /// * `capacity`: The maximum number of items the cache can hold.
+    /// * `window_ratio`: The fraction of the capacity given to the admission window. The window
+    ///   and main region always get at least one entry each.
+    /// * `protected_ratio`: The fraction of the main region given to the protected segment.
+    /// * `sketch_width`: The number of counters in each row of the frequency sketch.
+    /// * `sketch_depth`: The number of rows in the frequency sketch.
+    ///
+    /// # Returns
+    /// A `WTinyLFU` instance.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is less than 2, if either ratio is outside `0.0..=1.0`, or if the
+    /// sketch width or depth is zero.
+    pub fn new(capacity: usize, window_ratio: f64, protected_ratio: f64, sketch_width: usize, sketch_depth: usize) -> Self {
+        assert!(capacity >= 2, "W-TinyLFU needs a capacity of at least 2");
+        assert!(
+            (0.0..=1.0).contains(&window_ratio) && (0.0..=1.0).contains(&protected_ratio),
+            "window and protected ratios must be between 0 and 1"
+        );
+        let window_capacity = ((capacity as f64 * window_ratio + 0.5) as usize).clamp(1, capacity - 1);
+        let main_capacity = capacity - window_capacity;
+        WTinyLFU {
+            window: LRU::new(),
+            probationary: LRU::new(),
+            protected: LRU::new(),
+            sketch: CountMinSketch::new(sketch_width, sketch_depth),
+            window_capacity,
+            main_capacity,
+            protected_capacity: (main_capacity as f64 * protected_ratio) as usize,
+            samples: 0,
+            sample_size: capacity.saturating_mul(10),
+        }
+    }
+
+    /// Returns the combined capacity of the window and main region.
+    ///
+    /// # Returns
+    /// The total number of keys the policy tracks before it starts evicting.
+    pub fn capacity(&self) -> usize {
+        self.window_capacity + self.main_capacity
+    }
+
+    /// Returns the estimated number of recent uses of a key.
+    ///
+    /// # Parameters
+    /// * `key`: The key to look up.
+    ///
+    /// # Returns
+    /// The frequency sketch's estimate, which decays as the sketch is aged.
+    pub fn frequency(&self, key: &K) -> u64 {
+        self.sketch.estimate(key)
+    }
+
+    /// Counts a use of a key, aging the sketch once enough uses have been recorded.
+    fn record(&mut self, key: &K) {
+        self.sketch.increment(key);
+        self.samples += 1;
+        if self.samples >= self.sample_size {
+            self.sketch.age();
+            self.samples /= 2;
+        }
+    }
+
+    fn main_len(&self) -> usize {
+        self.probationary.len() + self.protected.len()
+    }
+
+    fn move_to_protected(&mut self, key: &K)
+    where
+        K: Send + Sync,
+    {
+        self.probationary.on_remove(key);
+        if self.protected.len() >= self.protected_capacity {
+            // Demote the least recently used protected key rather than forgetting it.
+            if let Some(demoted_key) = self.protected.evict() {
+                self.probationary.on_insert(&demoted_key);
+            }
+        }
+        if self.protected_capacity == 0 {
+            self.probationary.on_insert(key);
+        } else {
+            self.protected.on_insert(key);
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for WTinyLFU<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.current_time += 1;
-        self.use_order.insert(key.clone(), self.current_time);
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.record(key);
+        // An update of a tracked key keeps its current region.
+        if self.contains(key) {
+            return None;
+        }
+        self.window.on_insert(key);
+        if self.window.len() <= self.window_capacity {
+            return None;
+        }
+        // The window overflowed, so its least recent key moves on to the main region, either
+        // straight away if there is room or by competing with the main region's victim.
+        if self.main_len() < self.main_capacity {
+            let candidate = self.window.evict()?;
+            self.probationary.on_insert(&candidate);
+            return None;
+        }
+        self.evict()
     }
 
     fn on_access(&mut self, key: &K) {
-        self.current_time += 1;
-        self.use_order.insert(key.clone(), self.current_time);
+        self.record(key);
+        if self.window.contains(key) {
+            self.window.on_access(key);
+        } else if self.probationary.contains(key) {
+            self.move_to_protected(key);
+        } else {
+            self.protected.on_access(key);
+        }
     }
 
     fn on_remove(&mut self, key: &K) {
-        self.use_order.remove(key);
+        self.window.on_remove(key);
+        self.probationary.on_remove(key);
+        self.protected.on_remove(key);
     }
 
     fn evict(&mut self) -> Option<K> {
-        if let Some((&key, _)) = self.use_order.iter().max_by_key(|entry| entry.1) {
-            self.use_order.remove(&key);
-            Some(key)
+        let victim = self.probationary.least_recent().or_else(|| self.protected.least_recent());
+        let (Some(candidate), Some(victim)) = (self.window.least_recent(), victim) else {
+            return self.window.evict().or_else(|| self.probationary.evict()).or_else(|| self.protected.evict());
+        };
+        // The candidate is only admitted if it is used more often than the key it would replace
+        if self.sketch.estimate(&candidate) > self.sketch.estimate(&victim) {
+            self.window.on_remove(&candidate);
+            self.probationary.on_remove(&victim);
+            self.protected.on_remove(&victim);
+            self.probationary.on_insert(&candidate);
+            Some(victim)
         } else {
-            None
+            self.window.on_remove(&candidate);
+            Some(candidate)
         }
     }
+
+    fn len(&self) -> usize {
+        self.window.len() + self.main_len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.window.contains(key) || self.probationary.contains(key) || self.protected.contains(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(WTinyLFU::capacity(self))
+    }
+
+    fn name(&self) -> &'static str {
+        "WTinyLFU"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        let window = self.window.debug_state().into_iter().map(|(key, _)| (key, PolicyMetadata::Segment(0)));
+        let probationary = self.probationary.debug_state().into_iter().map(|(key, _)| (key, PolicyMetadata::Segment(1)));
+        let protected = self.protected.debug_state().into_iter().map(|(key, _)| (key, PolicyMetadata::Segment(2)));
+        window.chain(probationary).chain(protected).collect()
+    }
+
+    fn reset(&mut self) {
+        self.window.reset();
+        self.probationary.reset();
+        self.protected.reset();
+        self.sketch.reset();
+        self.samples = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Reserving for the whole cache in every segment would overallocate
+        self.window.reserve(additional.min(self.window_capacity));
+        self.probationary.reserve(additional.min(self.main_capacity));
+        self.protected.reserve(additional.min(self.protected_capacity));
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.window.shrink_to_fit();
+        self.probationary.shrink_to_fit();
+        self.protected.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
+    }
 }
 
 // ==============================================================================================
-//                                     Random Eviction Policy
+//                                  MaxIdle Eviction Adapter
 // ==============================================================================================
 
-/// Random Eviction Policy
-pub struct RandomEviction<K>
-where
-    K: Eq + Hash + Copy,
+/// Max Idle Time
+///
+/// Wraps another policy and additionally evicts keys that haven't been inserted or accessed for
+/// longer than a timeout. When the cache asks for a victim, the longest-idle key past the timeout
+/// is chosen before the inner policy is consulted. Idle keys are also handed out by
+/// `EvictionPolicy::sweep`, so `Cache::sweep` drops them even while the cache has room.
+///
+/// # Type Parameters
+/// * `P`: The wrapped policy, which decides among keys that are still fresh.
+/// * `K`: The type of the keys in the cache.
+pub struct MaxIdle<P, K>
+    where
+        P: EvictionPolicy<K>,
+        K: Eq + Hash + Copy,
 {
-    keys: HashMap<K, ()>
+    inner: P,
+    max_idle: Duration,
+    clock: Box<dyn Clock + Send + Sync>,
+    last_used: HashMap<K, Instant>,
 }
 
-impl<K> RandomEviction<K>
-where
-    K: Eq + Hash + Copy,
+impl<P, K> MaxIdle<P, K>
+    where
+        P: EvictionPolicy<K>,
+        K: Eq + Hash + Copy,
 {
-    /// Creates a new Random Eviction policy instance.
+    /// Creates a new MaxIdle adapter that reads the system clock.
+    ///
+    /// # Parameters
+    /// * `inner`: The policy to wrap.
+    /// * `max_idle`: How long a key may go unused before it is evicted.
     ///
     /// # Returns
-    /// A `RandomEviction` instance.
-    pub fn new() -> Self {
-        RandomEviction {
-            keys: HashMap::new()
+    /// A `MaxIdle` instance.
+    pub fn new(inner: P, max_idle: Duration) -> Self {
+        Self::with_clock(inner, max_idle, Box::new(DefaultClock))
+    }
+
+    /// Creates a new MaxIdle adapter that reads the given clock.
+    ///
+    /// # Parameters
+    /// * `inner`: The policy to wrap.
+    /// * `max_idle`: How long a key may go unused before it is evicted.
+    /// * `clock`: The time source, such as a `MockClock` shared with the cache in tests.
+    ///
+    /// # Returns
+    /// A `MaxIdle` instance.
+    pub fn with_clock(inner: P, max_idle: Duration, clock: Box<dyn Clock + Send + Sync>) -> Self {
+        MaxIdle {
+            inner,
+            max_idle,
+            clock,
+            last_used: HashMap::new(),
         }
     }
+
+    /// Returns the longest-idle key that has gone unused for longer than the timeout.
+    fn most_idle(&self) -> Option<K> {
+        let now = self.clock.now();
+        self.last_used
+            .iter()
+            .filter(|(_, &last_used)| now - last_used > self.max_idle)
+            .min_by_key(|(_, &last_used)| last_used)
+            .map(|(&key, _)| key)
+    }
 }
 
-impl<K> EvictionPolicy<K> for RandomEviction<K>
-where
-    K: Eq + Hash + Copy,
+impl<P, K> fmt::Debug for MaxIdle<P, K>
+    where
+        P: EvictionPolicy<K> + fmt::Debug,
+        K: Eq + Hash + Copy + fmt::Debug,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.keys.insert(key.clone(), ());
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaxIdle")
+            .field("inner", &self.inner)
+            .field("max_idle", &self.max_idle)
+            .field("last_used", &self.last_used)
+            .finish_non_exhaustive()
     }
+}
 
-    fn on_access(&mut self, _key: &K) {
-        // Do nothing on access
+impl<P, K> EvictionPolicy<K> for MaxIdle<P, K>
+    where
+        P: EvictionPolicy<K>,
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.last_used.insert(*key, self.clock.now());
+        let dropped_key = self.inner.on_insert(key)?;
+        self.last_used.remove(&dropped_key);
+        Some(dropped_key)
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some(last_used) = self.last_used.get_mut(key) {
+            *last_used = self.clock.now();
+        }
+        self.inner.on_access(key);
     }
 
     fn on_remove(&mut self, key: &K) {
-        self.keys.remove(key);
+        self.last_used.remove(key);
+        self.inner.on_remove(key);
     }
 
     fn evict(&mut self) -> Option<K> {
-        let mut rng = thread_rng();
-        self.keys.keys().choose(&mut rng).cloned()
+        if let Some(idle_key) = self.most_idle() {
+            self.on_remove(&idle_key);
+            return Some(idle_key);
+        }
+        let evicted_key = self.inner.evict()?;
+        self.last_used.remove(&evicted_key);
+        Some(evicted_key)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.inner.contains(key)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+
+    fn name(&self) -> &'static str {
+        "MaxIdle"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.inner.debug_state()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        self.inner.hotness(key)
+    }
+
+    fn sweep(&mut self) -> Vec<K> {
+        let now = self.clock.now();
+        let idle_keys: Vec<K> = self
+            .last_used
+            .iter()
+            .filter(|(_, &last_used)| now - last_used > self.max_idle)
+            .map(|(&key, _)| key)
+            .collect();
+        for key in &idle_keys {
+            self.on_remove(key);
+        }
+        idle_keys
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.last_used.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+        self.last_used.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+        self.last_used.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        let inner = self.inner.clone_box()?;
+        let clock = self.clock.clone_box()?;
+        Some(Box::new(MaxIdle::<Box<dyn EvictionPolicy<K>>, K> {
+            inner,
+            max_idle: self.max_idle,
+            clock,
+            last_used: self.last_used.clone(),
+        }))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
 // ==============================================================================================
-//                                     SLRU Eviction Policy
+//                                    TtlLru Eviction Policy
 // ==============================================================================================
 
-/// Segmented Least Recently Used
-pub struct SLRU<K>
+/// Least Recently Used with per-key time-to-live
+///
+/// Each key records when it was inserted and how long it may live. When the cache asks for a
+/// victim, the expired key that was inserted first is chosen; only when no key has expired does
+/// the least recently used one go. Expired keys are also handed out by `EvictionPolicy::sweep`,
+/// so `Cache::sweep` drops them even while the cache has room.
+pub struct TtlLru<K>
     where
         K: Eq + Hash + Copy,
 {
-    probationary: LRU<K>,
-    protected: LRU<K>,
-    probationary_capacity: usize,
-    protected_capacity: usize,
+    /// The last use time, insertion time and time-to-live of each key.
+    entries: HashMap<K, (usize, Instant, Duration)>,
+    default_ttl: Duration,
+    clock: Box<dyn Clock + Send + Sync>,
+    current_time: usize,
 }
 
-impl<K> SLRU<K>
+impl<K> TtlLru<K>
     where
         K: Eq + Hash + Copy,
 {
-    /// Creates a new SLRU eviction policy instance
+    /// Creates a new TtlLru eviction policy instance that reads the system clock.
     ///
     /// # Parameters
-    /// * `probationary_capacity`: The capacity of the probationary segment.
-    /// * `protected_capacity`: The capacity of the protected segment.
+    /// * `default_ttl`: How long a key lives after insertion unless `set_ttl` says otherwise.
     ///
     /// # Returns
-    /// An `SLRU` instance
-    pub fn new(probationary_capacity: usize, protected_capacity: usize) -> Self {
-        SLRU {
-            probationary: LRU::new(),
-            protected: LRU::new(),
-            probationary_capacity,
-            protected_capacity,
+    /// A `TtlLru` instance.
+    pub fn new(default_ttl: Duration) -> Self {
+        Self::with_clock(default_ttl, Box::new(DefaultClock))
+    }
+
+    /// Creates a new TtlLru eviction policy instance that reads the given clock.
+    ///
+    /// # Parameters
+    /// * `default_ttl`: How long a key lives after insertion unless `set_ttl` says otherwise.
+    /// * `clock`: The time source, such as a `MockClock` shared with the cache in tests.
+    ///
+    /// # Returns
+    /// A `TtlLru` instance.
+    pub fn with_clock(default_ttl: Duration, clock: Box<dyn Clock + Send + Sync>) -> Self {
+        TtlLru {
+            entries: HashMap::new(),
+            default_ttl,
+            clock,
+            current_time: 0,
         }
     }
 
-    fn move_to_protected(&mut self, key: &K) {
-        if self.probationary.use_order.contains_key(key) {
-            if self.protected.use_order.len() >= self.protected_capacity {
-                if let Some(evicted_key) = self.protected.evict() {
-                    self.protected.on_remove(&evicted_key);
-                }
+    /// Overrides the time-to-live of a tracked key, counting from when it was inserted.
+    ///
+    /// # Parameters
+    /// * `key`: The key whose time-to-live to change.
+    /// * `ttl`: How long the key lives after its insertion.
+    ///
+    /// # Returns
+    /// `true` if the key is tracked, or `false` if there was nothing to change.
+    pub fn set_ttl(&mut self, key: &K, ttl: Duration) -> bool {
+        match self.entries.get_mut(key) {
+            Some((_, _, key_ttl)) => {
+                *key_ttl = ttl;
+                true
             }
-            self.probationary.on_remove(key);
-            self.protected.on_insert(key);
+            None => false,
         }
     }
+
+    /// Returns the keys whose time-to-live has run out, inserted first.
+    fn expired(&self) -> Vec<K> {
+        let now = self.clock.now();
+        let mut expired: Vec<(Instant, K)> = self
+            .entries
+            .iter()
+            .filter(|(_, &(_, inserted, ttl))| inserted + ttl <= now)
+            .map(|(&key, &(_, inserted, _))| (inserted, key))
+            .collect();
+        expired.sort_by_key(|&(inserted, _)| inserted);
+        expired.into_iter().map(|(_, key)| key).collect()
+    }
 }
 
-impl<K> EvictionPolicy<K> for SLRU<K>
+impl<K> fmt::Debug for TtlLru<K>
     where
-        K: Eq + Hash + Copy,
+        K: Eq + Hash + Copy + fmt::Debug,
 {
-    fn on_insert(&mut self, key: &K) {
-        if self.probationary.use_order.len() >= self.probationary_capacity {
-            if let Some(evicted_key) = self.probationary.evict() {
-                self.probationary.on_remove(&evicted_key);
-            }
-        }
-        self.probationary.on_insert(key);
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TtlLru")
+            .field("entries", &self.entries)
+            .field("default_ttl", &self.default_ttl)
+            .field("current_time", &self.current_time)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K> EvictionPolicy<K> for TtlLru<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.current_time += 1;
+        self.entries.insert(*key, (self.current_time, self.clock.now(), self.default_ttl));
+        None
     }
 
     fn on_access(&mut self, key: &K) {
-        if self.probationary.use_order.contains_key(key) {
-            self.move_to_protected(key);
-        } else {
-            self.protected.on_access(key);
+        self.current_time += 1;
+        if let Some((last_used, _, _)) = self.entries.get_mut(key) {
+            *last_used = self.current_time;
         }
     }
 
     fn on_remove(&mut self, key: &K) {
-        if self.probationary.use_order.contains_key(key) {
-            self.probationary.on_remove(key);
-        } else {
-            self.protected.on_remove(key);
-        }
+        self.entries.remove(key);
     }
 
     fn evict(&mut self) -> Option<K> {
-        if let Some(evicted_key) = self.probationary.evict() {
-            return Some(evicted_key);
+        let key = match self.expired().first() {
+            Some(&key) => key,
+            None => *self.entries.iter().min_by_key(|(_, &(last_used, _, _))| last_used)?.0,
+        };
+        self.entries.remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "TtlLru"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.entries.iter().map(|(&key, &(last_used, _, _))| (key, PolicyMetadata::LastAccess(last_used))).collect()
+    }
+
+    fn sweep(&mut self) -> Vec<K> {
+        let expired = self.expired();
+        for key in &expired {
+            self.entries.remove(key);
         }
-        self.protected.evict()
+        expired
+    }
+
+    fn reset(&mut self) {
+        self.entries.clear();
+        self.current_time = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(TtlLru {
+            entries: self.entries.clone(),
+            default_ttl: self.default_ttl,
+            clock: self.clock.clone_box()?,
+            current_time: self.current_time,
+        }))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
 // ==============================================================================================
-//                                  SFIFO Eviction Policy
+//                                   Adaptive Eviction Policy
 // ==============================================================================================
 
-/// Segmented First In First Out
-pub struct SFIFO<K>
-where
-    K: Eq + Hash + Copy,
+/// Adaptive choice between two policies
+///
+/// The trusted policy tracks the cached keys and picks victims as usual. The other one runs as a
+/// shadow: it sees the same requests but keeps its own set of keys, as large as the cache, and
+/// drops keys by its own choice. The keys each policy has dropped act as its ghost list, so a
+/// request for one of them is a miss that policy would have had. After every `interval` requests
+/// the two miss counts are compared, and trust moves to the shadow if it missed less. The new
+/// trusted policy starts tracking any cached keys it had dropped, and keeps the history of keys
+/// the cache no longer holds, which it skips when picking victims. The old one becomes the
+/// shadow.
+pub struct Adaptive<K>
+    where
+        K: Eq + Hash + Copy,
 {
-    segments: Vec<VecDeque<K>>,
-    segment_capacity: usize,
+    policies: [Box<dyn EvictionPolicy<K>>; 2],
+    /// The index of the trusted policy.
+    active: usize,
+    interval: usize,
+    /// The keys the cache holds.
+    resident: HashSet<K>,
+    requests: usize,
+    /// Misses of each policy during the current interval.
+    misses: [usize; 2],
 }
 
-impl<K> SFIFO<K>
-where
-    K: Eq + Hash + Copy,
+impl<K> Adaptive<K>
+    where
+        K: Eq + Hash + Copy,
 {
-    /// Creates a new SFIFO eviction policy instance
+    /// Creates a new Adaptive eviction policy instance that starts out trusting `first`.
     ///
     /// # Parameters
-    /// * `num_seqments`: The number of segments to divide the cache into.
-    /// * `segment_capacity`: The maximum number of items each segment can hold.
+    /// * `first`: The policy trusted initially.
+    /// * `second`: The policy to compare it with.
+    /// * `interval`: How many requests pass between decisions about which policy to trust.
     ///
     /// # Returns
-    /// A `SFIFO` instance.
-    pub fn new(num_segments: usize, segment_capacity: usize) -> Self {
-        SFIFO {
-            segments: vec![VecDeque::new(); num_segments],
-            segment_capacity,
+    /// An `Adaptive` instance.
+    ///
+    /// # Panics
+    /// Panics if `interval` is zero, or if either policy manages its own capacity (see
+    /// `EvictionPolicy::capacity`), since the shadow must be able to grow to the cache's size.
+    pub fn new(first: Box<dyn EvictionPolicy<K>>, second: Box<dyn EvictionPolicy<K>>, interval: usize) -> Self {
+        assert!(interval > 0, "adaptation interval must be at least 1");
+        assert!(
+            first.capacity().is_none() && second.capacity().is_none(),
+            "adaptive policies must not manage their own capacity"
+        );
+        Adaptive {
+            policies: [first, second],
+            active: 0,
+            interval,
+            resident: HashSet::new(),
+            requests: 0,
+            misses: [0; 2],
         }
     }
 
-    /// Determines the segment index for a given key
-    ///
-    /// # Parameters
-    /// * `key`: The key to determine the segment for.
+    /// Returns the name of the policy currently picking victims.
     ///
     /// # Returns
-    /// The segment index
-    fn segment_index(&self, key: &K) -> usize {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() as usize) % self.segments.len()
+    /// The trusted policy's `EvictionPolicy::name`.
+    pub fn active_name(&self) -> &'static str {
+        self.policies[self.active].name()
+    }
+
+    /// Passes a request on to the shadow, which inserts the key if it had dropped it and then
+    /// drops keys of its own choosing until it is no larger than the cache.
+    fn shadow_request(&mut self, key: &K) {
+        let shadow = &mut self.policies[1 - self.active];
+        if shadow.contains(key) {
+            shadow.on_access(key);
+            return;
+        }
+        self.misses[1 - self.active] += 1;
+        shadow.on_insert(key);
+        while shadow.len() > self.resident.len() {
+            if shadow.evict().is_none() {
+                break;
+            }
+        }
+    }
+
+    /// Counts a request, trusting the shadow from now on if it missed less during the interval.
+    fn end_request(&mut self) {
+        self.requests += 1;
+        if !self.requests.is_multiple_of(self.interval) {
+            return;
+        }
+        if self.misses[1 - self.active] < self.misses[self.active] {
+            self.active = 1 - self.active;
+            let active = &mut self.policies[self.active];
+            for key in &self.resident {
+                if !active.contains(key) {
+                    active.on_insert(key);
+                }
+            }
+        }
+        self.misses = [0; 2];
     }
 }
 
-impl<K> EvictionPolicy<K> for SFIFO<K>
-where
-    K: Eq + Hash + Copy,
+impl<K> fmt::Debug for Adaptive<K>
+    where
+        K: Eq + Hash + Copy + fmt::Debug,
 {
-    fn on_insert(&mut self, key: &K) {
-        let index = self.segment_index(key);
-        if self.segments[index].len() >= self.segment_capacity {
-            self.segments[index].pop_front();
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Adaptive")
+            .field("policies", &[self.policies[0].name(), self.policies[1].name()])
+            .field("active", &self.active_name())
+            .field("interval", &self.interval)
+            .field("resident", &self.resident)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K> EvictionPolicy<K> for Adaptive<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        if !self.resident.insert(*key) {
+            // An update of a cached key isn't a request
+            self.policies[self.active].on_insert(key);
+            let shadow = &mut self.policies[1 - self.active];
+            if shadow.contains(key) {
+                shadow.on_insert(key);
+            }
+            return None;
         }
-        self.segments[index].push_back(*key);
+        self.misses[self.active] += 1;
+        // A key left over from the policy's time as the shadow keeps its history
+        let active = &mut self.policies[self.active];
+        if active.contains(key) {
+            active.on_access(key);
+        } else {
+            active.on_insert(key);
+        }
+        self.shadow_request(key);
+        self.end_request();
+        None
     }
 
-    fn on_access(&mut self, _key: &K) {
-        // Do nothing on access
+    fn on_access(&mut self, key: &K) {
+        if !self.resident.contains(key) {
+            return;
+        }
+        self.policies[self.active].on_access(key);
+        self.shadow_request(key);
+        self.end_request();
     }
 
     fn on_remove(&mut self, key: &K) {
-        let index = self.segment_index(key);
-        self.segments[index].retain(|x| x != key);
+        self.resident.remove(key);
+        for policy in &mut self.policies {
+            policy.on_remove(key);
+        }
     }
 
     fn evict(&mut self) -> Option<K> {
-        for segment in &mut self.segments {
-            if let Some(key) = segment.pop_front() {
+        loop {
+            let key = self.policies[self.active].evict()?;
+            if self.resident.remove(&key) {
                 return Some(key);
             }
         }
-        None
+    }
+
+    fn len(&self) -> usize {
+        self.resident.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.resident.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "Adaptive"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.policies[self.active].debug_state()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        self.policies[self.active].hotness(key)
+    }
+
+    fn reset(&mut self) {
+        for policy in &mut self.policies {
+            policy.reset();
+        }
+        self.active = 0;
+        self.resident.clear();
+        self.requests = 0;
+        self.misses = [0; 2];
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        for policy in &mut self.policies {
+            policy.reserve(additional);
+        }
+        self.resident.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        for policy in &mut self.policies {
+            policy.shrink_to_fit();
+        }
+        self.resident.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(Adaptive {
+            policies: [self.policies[0].clone_box()?, self.policies[1].clone_box()?],
+            active: self.active,
+            interval: self.interval,
+            resident: self.resident.clone(),
+            requests: self.requests,
+            misses: self.misses,
+        }))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
 // ==============================================================================================
-//                                  KLRU Eviction Policy
+//                                  Composite Eviction Policy
 // ==============================================================================================
 
-/// K-Largest Recently Used
-pub struct KLRU<K>
+/// Composite of a primary policy and a tiebreaker
+///
+/// Both policies track every key. The primary decides which keys are worth evicting, through
+/// `EvictionPolicy::victim_candidates`, and the secondary picks the one it values least among
+/// them, by `EvictionPolicy::hotness`. LFU with an LRU tiebreaker, for example, evicts the least
+/// recently used of the least frequently used keys. If the primary reports no candidates, its own
+/// `evict` choice is used, and if the secondary doesn't score keys the first candidate is.
+pub struct Composite<K>
     where
         K: Eq + Hash + Copy,
 {
-    use_order: VecDeque<K>,
-    k: usize,
+    primary: Box<dyn EvictionPolicy<K>>,
+    secondary: Box<dyn EvictionPolicy<K>>,
 }
 
-impl<K> KLRU<K>
+impl<K> Composite<K>
     where
         K: Eq + Hash + Copy,
 {
-    /// Creates a new KLRU eviction policy instance.
+    /// Creates a new Composite eviction policy instance.
     ///
     /// # Parameters
-    /// * `k`: The position of the key to be evicted.
+    /// * `primary`: The policy choosing the candidates for eviction.
+    /// * `secondary`: The policy breaking ties between the candidates.
     ///
     /// # Returns
-    /// A `KLRU` instance.
-    pub fn new(k: usize) -> Self {
-        KLRU {
-            use_order: VecDeque::new(),
-            k,
-        }
+    /// A `Composite` instance.
+    ///
+    /// # Panics
+    /// Panics if either policy manages its own capacity (see `EvictionPolicy::capacity`), since a
+    /// key one of them drops by itself would leave the two tracking different keys.
+    pub fn new(primary: Box<dyn EvictionPolicy<K>>, secondary: Box<dyn EvictionPolicy<K>>) -> Self {
+        assert!(
+            primary.capacity().is_none() && secondary.capacity().is_none(),
+            "composite policies must not manage their own capacity"
+        );
+        Composite { primary, secondary }
     }
 }
 
-impl<K> EvictionPolicy<K> for KLRU<K>
+impl<K> fmt::Debug for Composite<K>
     where
         K: Eq + Hash + Copy,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.use_order.push_back(*key);
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Composite")
+            .field("primary", &self.primary.name())
+            .field("secondary", &self.secondary.name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K> EvictionPolicy<K> for Composite<K>
+    where
+        K: Eq + Hash + Copy + Send + Sync,
+{
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.primary.on_insert(key);
+        self.secondary.on_insert(key);
+        None
     }
 
     fn on_access(&mut self, key: &K) {
-        self.use_order.retain(|x| x != key);
-        self.use_order.push_back(*key);
+        self.primary.on_access(key);
+        self.secondary.on_access(key);
     }
 
     fn on_remove(&mut self, key: &K) {
-        self.use_order.retain(|x| x != key);
+        self.primary.on_remove(key);
+        self.secondary.on_remove(key);
     }
 
     fn evict(&mut self) -> Option<K> {
-        if self.use_order.len() > self.k {
-            let evicted_key = self.use_order[self.use_order.len() - 1 - self.k];
-            self.use_order.retain(|x| x != &evicted_key);
-            Some(evicted_key)
-        } else {
-            None
-        }
+        let candidates = self.primary.victim_candidates();
+        // Unscored candidates count as the most valuable, so a scored one is preferred
+        let score = |key: &K| self.secondary.hotness(key).unwrap_or(1.0);
+        let Some(&key) = candidates.iter().min_by(|a, b| score(a).total_cmp(&score(b))) else {
+            let key = self.primary.evict()?;
+            self.secondary.on_remove(&key);
+            return Some(key);
+        };
+        self.on_remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.primary.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "Composite"
+    }
+
+    fn debug_state(&self) -> Vec<(K, PolicyMetadata)> {
+        self.primary.debug_state()
+    }
+
+    fn hotness(&self, key: &K) -> Option<f64> {
+        self.primary.hotness(key)
+    }
+
+    fn reset(&mut self) {
+        self.primary.reset();
+        self.secondary.reset();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.primary.reserve(additional);
+        self.secondary.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.primary.shrink_to_fit();
+        self.secondary.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(Composite {
+            primary: self.primary.clone_box()?,
+            secondary: self.secondary.clone_box()?,
+        }))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
 // ==============================================================================================
-//                                  Second-Chance Eviction Policy
+//                                  Optimal Eviction Policy
 // ==============================================================================================
 
-/// Second-Chance Eviction Policy
-pub struct SecondChance<K>
+/// Belady's Optimal (MIN) Eviction Policy
+///
+/// Evicts the key whose next use lies farthest in the future. The policy must be given the entire
+/// access sequence up front, so it is only useful for offline analysis: replaying a known trace to
+/// find the best hit rate any policy could achieve, as a baseline for comparing the others.
+///
+/// Every `on_insert` and `on_access` call advances an internal cursor through the sequence, so the
+/// policy must be driven with exactly the keys of `future`, in order.
+#[derive(Clone, Debug)]
+pub struct Optimal<K>
     where
         K: Eq + Hash + Copy,
 {
-    queue: VecDeque<(K, bool)>,
+    next_uses: HashMap<K, VecDeque<usize>>,
+    resident: HashSet<K>,
+    cursor: usize,
 }
 
-impl<K> SecondChance<K>
+impl<K> Optimal<K>
     where
         K: Eq + Hash + Copy,
 {
-    /// Creates a new Second-Chance eviction policy instance
+    /// Creates a new Optimal eviction policy instance.
+    ///
+    /// # Parameters
+    /// * `future`: The complete sequence of keys that will be accessed, in order.
     ///
     /// # Returns
-    /// A `SecondChance` instance.
-    pub fn new() -> Self {
-        SecondChance {
-            queue: VecDeque::new(),
+    /// An `Optimal` instance.
+    pub fn new(future: Vec<K>) -> Self {
+        let mut next_uses: HashMap<K, VecDeque<usize>> = HashMap::new();
+        for (position, key) in future.into_iter().enumerate() {
+            next_uses.entry(key).or_default().push_back(position);
+        }
+        Optimal {
+            next_uses,
+            resident: HashSet::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Consumes the current position of the access sequence.
+    fn advance(&mut self, key: &K) {
+        if let Some(uses) = self.next_uses.get_mut(key) {
+            while uses.front().is_some_and(|&position| position <= self.cursor) {
+                uses.pop_front();
+            }
         }
+        self.cursor += 1;
+    }
+
+    /// Returns the position of the next use of `key`, or `usize::MAX` if it is never used again.
+    fn next_use(&self, key: &K) -> usize {
+        self.next_uses
+            .get(key)
+            .and_then(|uses| uses.iter().find(|&&position| position >= self.cursor))
+            .copied()
+            .unwrap_or(usize::MAX)
     }
 }
 
-impl<K> EvictionPolicy<K> for SecondChance<K>
+impl<K> EvictionPolicy<K> for Optimal<K>
     where
-        K: Eq + Hash + Copy,
+        K: Eq + Hash + Copy + Send + Sync,
 {
-    fn on_insert(&mut self, key: &K) {
-        self.queue.push_back((*key, false));
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        self.resident.insert(*key);
+        self.advance(key);
+        None
     }
 
     fn on_access(&mut self, key: &K) {
-        if let Some((_, ref mut referenced)) = self.queue.iter_mut().find(|(k, _)| k == key) {
-            *referenced = true;
-        }
+        self.advance(key);
     }
 
     fn on_remove(&mut self, key: &K) {
-        self.queue.retain(|(k, _)| k != key);
+        self.resident.remove(key);
     }
 
     fn evict(&mut self) -> Option<K> {
-        while let Some((key, referenced)) = self.queue.pop_front() {
-            if referenced {
-                self.queue.push_back((key, false));
-            } else {
-                return Some(key);
-            }
-        }
-        None
+        let key = *self.resident.iter().max_by_key(|key| self.next_use(key))?;
+        self.resident.remove(&key);
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.resident.len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.resident.contains(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "Optimal"
+    }
+
+    fn reset(&mut self) {
+        // The future trace is kept, so only residency is forgotten
+        self.resident.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // The future trace was sized up front
+        self.resident.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.next_uses.shrink_to_fit();
+        self.resident.shrink_to_fit();
+    }
+
+    fn clone_box(&self) -> Option<Box<dyn EvictionPolicy<K>>>
+    where
+        Self: 'static,
+    {
+        Some(Box::new(self.clone()))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
 
 // ==============================================================================================
-//                                  ARC Eviction Policy
+//                                   Closure Eviction Policy
 // ==============================================================================================
 
-/// Adaptive Replacement Cache
-pub struct ARC<K>
-    where
-        K: Eq + Hash + Copy,
+/// Closure Eviction Policy
+///
+/// Tracks the cached keys and leaves the choice of victim to a closure, which is handed every
+/// tracked key and returns the one to evict. This makes it quick to prototype a policy inline
+/// without implementing `EvictionPolicy`. Keys are passed in arbitrary order, and a key the closure
+/// returns that isn't tracked evicts nothing.
+///
+/// The closure needn't be `Clone`, so a cache using this policy can't be cloned.
+pub struct FnPolicy<K, F>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&[K]) -> Option<K>,
 {
-    t1: VecDeque<K>,
-    t2: VecDeque<K>,
-    b1: VecDeque<K>,
-    b2: VecDeque<K>,
-    p: usize,
-    capacity: usize,
+    keys: Vec<K>,
+    positions: HashMap<K, usize>,
+    choose: F,
 }
 
-impl<K> ARC<K>
-    where
-        K: Eq + Hash + Copy,
+impl<K, F> FnPolicy<K, F>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&[K]) -> Option<K>,
 {
-    /// Creates a new ARC eviction policy instance
+    /// Creates a new Closure Eviction policy instance.
     ///
     /// # Parameters
-    /// * `capacity`: The maximum number of items the cache can hold.
+    /// * `choose`: Picks the key to evict from the tracked keys, or `None` to evict nothing.
     ///
     /// # Returns
-    /// An `ARC` instance.
-    pub fn new(capacity: usize) -> Self {
-        ARC {
-            t1: VecDeque::new(),
-            t2: VecDeque::new(),
-            b1: VecDeque::new(),
-            b2: VecDeque::new(),
-            p: 0,
-            capacity,
+    /// A `FnPolicy` instance.
+    pub fn new(choose: F) -> Self {
+        FnPolicy {
+            keys: Vec::new(),
+            positions: HashMap::new(),
+            choose,
         }
     }
+}
 
-    fn replace(&mut self, key: &K) {
-        if !self.t1.is_empty() && (self.t1.len() > self.p || (self.b2.contains(key) && self.t1.len() == self.p)) {
-            let old = self.t1.pop_front().unwrap();
-            self.b1.push_back(old);
-        } else {
-            let old = self.t2.pop_front().unwrap();
-            self.b2.push_back(old);
-        }
+impl<K, F> fmt::Debug for FnPolicy<K, F>
+where
+    K: Eq + Hash + Clone + fmt::Debug,
+    F: FnMut(&[K]) -> Option<K>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnPolicy").field("keys", &self.keys).finish_non_exhaustive()
     }
 }
 
-impl<K> EvictionPolicy<K> for ARC<K>
-    where
-        K: Eq + Hash + Copy,
+impl<K, F> EvictionPolicy<K> for FnPolicy<K, F>
+where
+    K: Eq + Hash + Clone + Send + Sync,
+    F: FnMut(&[K]) -> Option<K> + Send + Sync,
 {
-    fn on_insert(&mut self, key: &K) {
-        if self.t1.contains(key) || self.t2.contains(key) {
-            return;
+    fn on_insert(&mut self, key: &K) -> Option<K> {
+        if !self.positions.contains_key(key) {
+            self.positions.insert(key.clone(), self.keys.len());
+            self.keys.push(key.clone());
         }
+        None
+    }
 
-        if self.t1.len() + self.b1.len() == self.capacity {
-            if self.t1.len() < self.capacity {
-                self.b1.pop_front();
-                self.replace(key);
-            } else {
-                self.t1.pop_front();
-            }
-        } else if self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len() >= self.capacity {
-            if self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len() == 2 * self.capacity {
-                self.b2.pop_front();
+    fn on_access(&mut self, _key: &K) {
+        // Do nothing on access
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(position) = self.positions.remove(key) {
+            self.keys.swap_remove(position);
+            if let Some(moved) = self.keys.get(position) {
+                self.positions.insert(moved.clone(), position);
             }
-            self.replace(key);
         }
-
-        self.t1.push_back(*key);
     }
 
-    fn on_access(&mut self, key: &K) {
-        if self.t1.contains(key) {
-            self.t1.retain(|x| x != key);
-            self.t2.push_back(*key);
-        } else if self.t2.contains(key) {
-            self.t2.retain(|x| x != key);
-            self.t2.push_back(*key);
-        } else if self.b1.contains(key) {
-            self.p = std::cmp::min(self.capacity, self.p + std::cmp::max(self.b2.len() / self.b1.len(), 1));
-            self.replace(key);
-            self.b1.retain(|x| x != key);
-            self.t2.push_back(*key);
-        } else if self.b2.contains(key) {
-            self.p = std::cmp::max(0, self.p as isize - std::cmp::max(self.b1.len() / self.b2.len(), 1) as isize) as usize;
-            self.replace(key);
-            self.b2.retain(|x| x != key);
-            self.t2.push_back(*key);
+    fn evict(&mut self) -> Option<K> {
+        let key = (self.choose)(&self.keys)?;
+        if !self.positions.contains_key(&key) {
+            return None;
         }
+        self.on_remove(&key);
+        Some(key)
     }
 
-    fn on_remove(&mut self, key: &K) {
-        self.t1.retain(|x| x != key);
-        self.t2.retain(|x| x != key);
-        self.b1.retain(|x| x != key);
-        self.b2.retain(|x| x != key);
+    fn len(&self) -> usize {
+        self.keys.len()
     }
 
-    fn evict(&mut self) -> Option<K> {
-        if self.t1.is_empty() && self.t2.is_empty() {
-            None
-        } else if self.t1.len() > self.p {
-            self.t1.pop_front()
-        } else {
-            self.t2.pop_front()
-        }
+    fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    fn name(&self) -> &'static str {
+        "FnPolicy"
+    }
+
+    fn reset(&mut self) {
+        self.keys.clear();
+        self.positions.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.positions.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.positions.shrink_to_fit();
+    }
+
+    fn as_any(&self) -> Option<&dyn Any>
+    where
+        Self: 'static,
+    {
+        Some(self)
     }
 }
@@ -4,16 +4,74 @@
 //! A lightweight caching library for Rust.
 //!
 //! Modules:
+//! - analysis: Helpers for comparing eviction policies on recorded traces.
+//! - array_cache: Provides a fixed-capacity LRU cache that never allocates.
+//! - backing: Defines loader and writer traits for a backing store.
+//! - builder: Provides a builder for configuring a cache.
 //! - cache: Provides the main cache struct and its associated methods.
+//! - clock: Defines the time source used to expire entries.
+//! - error: Defines the errors reported for invalid configurations and entries that don't fit.
 //! - eviction: Defines eviction policies for cache management.
+//! - observer: Defines hooks for reporting cache activity.
+//! - size: Estimates the memory used by keys and values.
+//! - sketch: Provides approximate frequency counting for frequency-based policies.
+//! - snapshot: Provides a shared cache with lock-free reads, behind the `snapshot` feature.
+//! - stats: Tracks hit, miss and eviction counters.
 //! - store: Implements the storage layer for the cache.
+//! - sync: Provides a thread-safe cache with single-flight loading.
+//! - tiered: Provides a two-tier cache with demotion and promotion between tiers.
+//! - weigher: Defines how entries are weighed for weight-bounded caches.
 //! - utils: Contains utility functions and helpers.
 //!
+//! The `std` feature is enabled by default. Without it the crate is `no_std` and only needs
+//! `alloc`; `SyncCache`, `SystemClock`, `MockClock` and `DecayingLFU` are unavailable, and entries
+//! only expire if the cache is given a `Clock`.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod collections;
 
+pub mod analysis;
+pub mod array_cache;
+pub mod backing;
+pub mod builder;
 pub mod cache;
+pub mod clock;
+pub mod error;
 pub mod eviction;
+pub mod observer;
+pub mod size;
+pub mod sketch;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod stats;
 pub mod store;
+#[cfg(feature = "std")]
+pub mod sync;
+pub mod tiered;
+pub mod weigher;
 
-pub use cache::Cache;
-pub use eviction::{ LRU, FIFO, LFU, MRU, RandomEviction, SLRU, SFIFO, KLRU, SecondChance, ARC };
-pub use store::Store;
\ No newline at end of file
+pub use array_cache::ArrayCache;
+pub use backing::{ Loader, Writer, WriteMode };
+pub use builder::CacheBuilder;
+pub use cache::{ Cache, CacheView, GrowOnPressure, Lookup, OnEvict, OnEvictOwned, OverflowPolicy, SetOutcome };
+pub use clock::{ Clock, Instant };
+#[cfg(feature = "std")]
+pub use clock::{ SystemClock, MockClock };
+pub use error::{ CacheError, InsertError };
+#[cfg(feature = "std")]
+pub use eviction::DecayingLFU;
+pub use eviction::{ LRU, FIFO, RingFifo, LFU, LFUDA, MRU, RandomEviction, SampledLru, SLRU, SFIFO, KLRU, LruK, SecondChance, ARC, WTinyLFU, MaxIdle, TtlLru, Adaptive, Composite, Optimal, FnPolicy, PolicyMetadata };
+pub use observer::{ Observer, NoopObserver };
+pub use sketch::CountMinSketch;
+#[cfg(feature = "snapshot")]
+pub use snapshot::SnapshotCache;
+pub use stats::CacheStats;
+pub use store::Store;
+#[cfg(feature = "std")]
+pub use sync::SyncCache;
+pub use tiered::TieredCache;
+pub use weigher::Weigher;
\ No newline at end of file
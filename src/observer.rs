@@ -0,0 +1,42 @@
+//!
+//! observer.rs
+//!
+//! Defines hooks for reporting cache activity to external metrics or tracing.
+//!
+
+/// Receives notifications about cache activity.
+///
+/// Every method has an empty default, so an observer only implements the events it cares about.
+/// A cache notifies its observer from `get` (hits and misses), `set` (inserts and evictions) and
+/// when deferred `get_shared` lookups are applied.
+pub trait Observer<K> {
+    /// Called when a lookup finds a value.
+    ///
+    /// # Parameters
+    /// * `key`: The key that was found.
+    fn on_hit(&mut self, _key: &K) {}
+
+    /// Called when a lookup finds nothing, including when the entry had expired.
+    ///
+    /// # Parameters
+    /// * `key`: The key that was looked up.
+    fn on_miss(&mut self, _key: &K) {}
+
+    /// Called when a key-value pair is inserted or updated.
+    ///
+    /// # Parameters
+    /// * `key`: The key that was inserted.
+    fn on_insert(&mut self, _key: &K) {}
+
+    /// Called when an entry is evicted to make room for another.
+    ///
+    /// # Parameters
+    /// * `key`: The key that was evicted.
+    fn on_evict(&mut self, _key: &K) {}
+}
+
+/// An observer that ignores every event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl<K> Observer<K> for NoopObserver {}
@@ -0,0 +1,88 @@
+//!
+//! size.rs
+//!
+//! Estimates the memory used by cached keys and values.
+//!
+//! `Cache::memory_estimate` needs `SizeOf` for both keys and values; `Cache::memory_estimate_with`
+//! takes sizing functions for types that lack it.
+//!
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+/// Reports the approximate number of bytes a value occupies, including heap memory it owns.
+///
+/// The default implementation returns the value's inline size, which is exact for types that
+/// own no heap memory. Such types can opt in with an empty impl:
+///
+/// ```
+/// use cache_lib::size::SizeOf;
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl SizeOf for Point {}
+///
+/// assert_eq!(Point { x: 1, y: 2 }.size_of(), 8);
+/// ```
+///
+/// Types from other crates can't be given an impl outside those crates, so caches holding them
+/// are sized with `Cache::memory_estimate_with` instead.
+pub trait SizeOf {
+    /// Returns the approximate size of the value in bytes.
+    ///
+    /// # Returns
+    /// The inline size of the value plus any heap memory it owns.
+    fn size_of(&self) -> usize {
+        mem::size_of_val(self)
+    }
+}
+
+macro_rules! impl_inline_size_of {
+    ($($type:ty),* $(,)?) => {
+        $(impl SizeOf for $type {})*
+    };
+}
+
+impl_inline_size_of!(
+    (), bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64,
+);
+
+impl SizeOf for String {
+    fn size_of(&self) -> usize {
+        mem::size_of::<String>() + self.capacity()
+    }
+}
+
+impl<T: SizeOf> SizeOf for Vec<T> {
+    fn size_of(&self) -> usize {
+        let unused = (self.capacity() - self.len()) * mem::size_of::<T>();
+        mem::size_of::<Vec<T>>() + unused + self.iter().map(SizeOf::size_of).sum::<usize>()
+    }
+}
+
+impl<T: SizeOf> SizeOf for Box<T> {
+    fn size_of(&self) -> usize {
+        mem::size_of::<Box<T>>() + (**self).size_of()
+    }
+}
+
+impl<T: SizeOf> SizeOf for Option<T> {
+    fn size_of(&self) -> usize {
+        match self {
+            // The payload's heap memory is counted on top of the option's inline size.
+            Some(value) => mem::size_of::<Option<T>>() - mem::size_of::<T>() + value.size_of(),
+            None => mem::size_of::<Option<T>>(),
+        }
+    }
+}
+
+impl<A: SizeOf, B: SizeOf> SizeOf for (A, B) {
+    fn size_of(&self) -> usize {
+        mem::size_of::<(A, B)>() - mem::size_of::<A>() - mem::size_of::<B>() + self.0.size_of() + self.1.size_of()
+    }
+}
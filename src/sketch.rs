@@ -0,0 +1,141 @@
+//!
+//! sketch.rs
+//!
+//! Provides approximate frequency counting for frequency-based eviction policies.
+//!
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{ Hash, Hasher };
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(not(feature = "std"))]
+use crate::collections::FnvHasher as DefaultHasher;
+
+/// Count-Min Sketch (Cormode and Muthukrishnan)
+///
+/// Counts how often keys are seen in a fixed amount of memory, however many distinct keys there
+/// are. Each key increments one counter in each of `depth` rows, and its estimate is the smallest
+/// of those counters. Collisions only ever add to a counter, so an estimate is never below the
+/// true count; with `N` increments in total, it exceeds the true count by more than `e * N /
+/// width` with probability at most `e^-depth`.
+///
+/// # Type Parameters
+/// * `K`: The type of the counted keys. Must implement `Hash`.
+#[derive(Clone)]
+pub struct CountMinSketch<K>
+where
+    K: Hash,
+{
+    /// The counters, one row of `width` after another.
+    counters: Vec<u64>,
+    width: usize,
+    depth: usize,
+    _keys: PhantomData<fn(&K)>,
+}
+
+/// Shows the sketch's dimensions rather than its counters, which are meaningless on their own.
+impl<K> fmt::Debug for CountMinSketch<K>
+where
+    K: Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountMinSketch")
+            .field("width", &self.width)
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K> CountMinSketch<K>
+where
+    K: Hash,
+{
+    /// Creates a new CountMinSketch with every counter at zero.
+    ///
+    /// # Parameters
+    /// * `width`: The number of counters in each row. Wider rows collide less often.
+    /// * `depth`: The number of rows. Deeper sketches are less likely to overestimate badly.
+    ///
+    /// # Returns
+    /// A `CountMinSketch` instance.
+    ///
+    /// # Panics
+    /// Panics if `width` or `depth` is zero.
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "sketch width and depth must be at least 1");
+        CountMinSketch {
+            counters: vec![0; width * depth],
+            width,
+            depth,
+            _keys: PhantomData,
+        }
+    }
+
+    /// Records one occurrence of a key.
+    ///
+    /// # Parameters
+    /// * `key`: The key to count.
+    pub fn increment(&mut self, key: &K) {
+        for index in self.indices(key) {
+            self.counters[index] = self.counters[index].saturating_add(1);
+        }
+    }
+
+    /// Estimates how often a key has been seen since the sketch was created or reset.
+    ///
+    /// # Parameters
+    /// * `key`: The key to look up.
+    ///
+    /// # Returns
+    /// The estimated count. It is never less than the true count unless the sketch was aged.
+    pub fn estimate(&self, key: &K) -> u64 {
+        self.indices(key).map(|index| self.counters[index]).min().unwrap_or(0)
+    }
+
+    /// Halves every counter, so recent occurrences outweigh old ones.
+    pub fn age(&mut self) {
+        for counter in &mut self.counters {
+            *counter /= 2;
+        }
+    }
+
+    /// Sets every counter back to zero.
+    pub fn reset(&mut self) {
+        self.counters.fill(0);
+    }
+
+    /// Returns the number of counters in each row.
+    ///
+    /// # Returns
+    /// The width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    ///
+    /// # Returns
+    /// The depth.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the index of the key's counter in each row.
+    ///
+    /// The rows' hashes are derived from a single hash of the key by double hashing, so the key
+    /// is only hashed once.
+    fn indices(&self, key: &K) -> impl Iterator<Item = usize> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+        let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        let width = self.width;
+        (0..self.depth).map(move |row| {
+            let column = h1.wrapping_add((row as u64).wrapping_mul(h2)) % width as u64;
+            row * width + column as usize
+        })
+    }
+}
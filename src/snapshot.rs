@@ -0,0 +1,138 @@
+//!
+//! snapshot.rs
+//!
+//! Provides a shared cache whose reads never wait for writers.
+//!
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{ Arc, Mutex };
+use arc_swap::ArcSwap;
+use crate::cache::Cache;
+use crate::eviction::EvictionPolicy;
+
+/// A read-mostly cache that can be shared between threads, with lock-free reads.
+///
+/// Readers look values up in an immutable snapshot of the cache, which writers replace
+/// atomically. A `get` therefore never blocks, even while a write is in progress, and sees either
+/// the snapshot from before the write or the one after it. Writes go through the wrapped `Cache`
+/// one at a time, so eviction and capacity work as usual, and then publish a new snapshot.
+///
+/// Publishing copies every key and value handle into a new map, so each write costs time
+/// proportional to the size of the cache. This suits caches that are read far more often than
+/// they are written; `SyncCache` is the better fit for write-heavy workloads. Reads aren't
+/// reported to the eviction policy, so it sees insertion order only, and entries that expire
+/// stay visible until the next write publishes a snapshot without them.
+///
+/// # Type Parameters
+/// * `K`: The type of the keys in the cache.
+/// * `V`: The type of the values in the cache. Values are stored behind an `Arc`, so readers get
+///   cheap handles rather than clones.
+/// * `P`: The eviction policy.
+pub struct SnapshotCache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    cache: Mutex<Cache<K, Arc<V>, P>>,
+    snapshot: ArcSwap<HashMap<K, Arc<V>>>,
+}
+
+impl<K, V, P> SnapshotCache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Creates a new SnapshotCache wrapping an existing cache.
+    ///
+    /// # Parameters
+    /// * `cache`: The cache to share. Its current entries form the first snapshot.
+    ///
+    /// # Returns
+    /// A `SnapshotCache` instance.
+    pub fn new(cache: Cache<K, Arc<V>, P>) -> Self {
+        let snapshot = ArcSwap::from_pointee(Self::capture(&cache));
+        SnapshotCache {
+            cache: Mutex::new(cache),
+            snapshot,
+        }
+    }
+
+    /// Inserts a key-value pair and publishes a new snapshot.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    pub fn set(&self, key: K, value: V) {
+        let mut cache = self.lock();
+        cache.set(key, Arc::new(value));
+        self.publish(&cache);
+    }
+
+    /// Retrieves a handle to the value associated with a given key, without locking.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a new reference to the value, or `None` if no value is found in
+    /// the current snapshot.
+    pub fn get(&self, key: &K) -> Option<Arc<V>> {
+        self.snapshot.load().get(key).cloned()
+    }
+
+    /// Removes a key-value pair and publishes a new snapshot.
+    ///
+    /// # Parameters
+    /// * `key`: The key to remove.
+    ///
+    /// # Returns
+    /// An `Option` containing the removed value if it exists, or `None` if no value is found.
+    pub fn remove(&self, key: &K) -> Option<Arc<V>> {
+        let mut cache = self.lock();
+        let removed = cache.remove(key);
+        self.publish(&cache);
+        removed
+    }
+
+    /// Returns the current snapshot, for reading several entries from one consistent view.
+    ///
+    /// # Returns
+    /// The key-value pairs as of the last write.
+    pub fn snapshot(&self) -> Arc<HashMap<K, Arc<V>>> {
+        self.snapshot.load_full()
+    }
+
+    /// Returns the number of key-value pairs in the current snapshot.
+    ///
+    /// # Returns
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.snapshot.load().len()
+    }
+
+    /// Checks to see if the current snapshot is empty.
+    ///
+    /// # Returns
+    /// `true` if the snapshot holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.load().is_empty()
+    }
+
+    /// Replaces the snapshot with the cache's current contents.
+    fn publish(&self, cache: &Cache<K, Arc<V>, P>) {
+        self.snapshot.store(Arc::new(Self::capture(cache)));
+    }
+
+    /// Copies the cache's unexpired entries into a new map.
+    fn capture(cache: &Cache<K, Arc<V>, P>) -> HashMap<K, Arc<V>> {
+        cache
+            .keys()
+            .filter_map(|key| Some((key.clone(), cache.peek(key)?.clone())))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache<K, Arc<V>, P>> {
+        self.cache.lock().unwrap()
+    }
+}
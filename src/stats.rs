@@ -0,0 +1,36 @@
+//!
+//! stats.rs
+//!
+//! Tracks hit, miss and eviction counters for a cache.
+//!
+
+/// Counters describing how a cache has been used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of lookups that found a value.
+    pub hits: u64,
+    /// The number of lookups that found nothing.
+    pub misses: u64,
+    /// The number of entries the cache removed on its own, the sum of `capacity_evictions` and
+    /// `ttl_expirations`.
+    pub evictions: u64,
+    /// The number of entries removed to make room for new ones.
+    pub capacity_evictions: u64,
+    /// The number of entries removed because their time-to-live ran out.
+    pub ttl_expirations: u64,
+}
+
+impl CacheStats {
+    /// Returns the fraction of lookups that were hits.
+    ///
+    /// # Returns
+    /// A ratio between `0.0` and `1.0`, or `0.0` if there have been no lookups.
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
@@ -4,14 +4,15 @@
 //! Establishes the storage layer for the cache.
 //!
 
-use std::collections::HashMap;
-use std::hash::Hash;
+use core::hash::Hash;
+use crate::collections::HashMap;
 
 /// Store struct for managing the storage of cache entries.
 ///
 /// # Type Parameters
 /// * `K`: The type of the keys in the cache. Must implement `Eq` and `Hash`.
 /// * `V`: The type of values in the cache.
+#[derive(Clone, Debug)]
 pub struct Store<K, V>
 where
     K: Eq + Hash,
@@ -33,6 +34,19 @@ where
         }
     }
 
+    /// Creates a new `Store` instance with room for `capacity` entries before it reallocates.
+    ///
+    /// # Parameters
+    /// * `capacity`: The number of entries to allocate room for.
+    ///
+    /// # Returns
+    /// A `Store` instance.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Store {
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
     /// Inserts a key-value pair into the store.
     /// If the key already exists, it updates the value.
     ///
@@ -54,6 +68,17 @@ where
         self.entries.get(key)
     }
 
+    /// Retrieves a mutable reference to the value associated with a given key.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a mutable reference to the value, or `None` if no value is found.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.entries.get_mut(key)
+    }
+
     /// Removes a key-value pair from the cache.
     ///
     /// # Parameters
@@ -65,6 +90,11 @@ where
         self.entries.remove(key)
     }
 
+    /// Shrinks the capacity of the store as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
     /// Checks to see if the store contains a key-value pair for the given key.
     ///
     /// # Parameters
@@ -75,4 +105,58 @@ where
     pub fn contains_key(&self, key: &K) -> bool {
         self.entries.contains_key(key)
     }
+
+    /// Returns an iterator over the keys in the store, in arbitrary order.
+    ///
+    /// # Returns
+    /// An iterator yielding a reference to each key.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.keys()
+    }
+
+    /// Returns an iterator over the key-value pairs in the store, in arbitrary order.
+    ///
+    /// # Returns
+    /// An iterator yielding a reference to each key and its value.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter()
+    }
+
+    /// Returns an iterator over the key-value pairs in the store with mutable values.
+    ///
+    /// # Returns
+    /// An iterator yielding a reference to each key and a mutable reference to its value.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.entries.iter_mut()
+    }
+
+    /// Removes all key-value pairs from the store.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the number of key-value pairs in the store.
+    ///
+    /// # Returns
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks to see if the store is empty.
+    ///
+    /// # Returns
+    /// `true` if the store holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K, V> Default for Store<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,146 @@
+//!
+//! sync.rs
+//!
+//! Provides a thread-safe wrapper around Cache.
+//!
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{ Arc, Mutex, OnceLock };
+use crate::cache::Cache;
+use crate::eviction::EvictionPolicy;
+
+/// A thread-safe cache that can be shared between threads.
+///
+/// Every operation locks the wrapped `Cache`, so values are returned as clones rather than
+/// references. Misses resolved through `get_or_insert_with` are single-flight: concurrent callers
+/// missing on the same key wait for one computation and share its result.
+///
+/// # Type Parameters
+/// * `K`: The type of the keys in the cache.
+/// * `V`: The type of the values in the cache. Must implement `Clone`.
+/// * `P`: The eviction policy, either a concrete policy type or a boxed trait object.
+pub struct SyncCache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    P: EvictionPolicy<K>,
+{
+    cache: Mutex<Cache<K, V, P>>,
+    in_flight: Mutex<HashMap<K, Arc<OnceLock<V>>>>,
+}
+
+impl<K, V, P> SyncCache<K, V, P>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    /// Creates a new SyncCache wrapping an existing cache.
+    ///
+    /// # Parameters
+    /// * `cache`: The cache to share.
+    ///
+    /// # Returns
+    /// A `SyncCache` instance.
+    pub fn new(cache: Cache<K, V, P>) -> Self {
+        SyncCache {
+            cache: Mutex::new(cache),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Inserts a key-value pair into the cache.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    pub fn set(&self, key: K, value: V) {
+        self.lock().set(key, value);
+    }
+
+    /// Retrieves a copy of the value associated with a given key.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing a clone of the value, or `None` if no value is found.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.lock().get(key).cloned()
+    }
+
+    /// Removes a key-value pair from the cache.
+    ///
+    /// # Parameters
+    /// * `key`: The key to remove.
+    ///
+    /// # Returns
+    /// An `Option` containing the removed value if it exists, or `None` if no value is found.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.lock().remove(key)
+    }
+
+    /// Retrieves the value for a key, computing and inserting it on a miss.
+    ///
+    /// If several threads miss on the same key at once, only one runs `f`; the others block
+    /// until it finishes and receive the same value. The cache itself isn't locked while `f`
+    /// runs, so lookups of other keys proceed normally.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    /// * `f`: Computes the value if the key isn't cached.
+    ///
+    /// # Returns
+    /// A clone of the cached or newly computed value.
+    pub fn get_or_insert_with<F>(&self, key: K, f: F) -> V
+    where
+        F: FnOnce() -> V,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+
+        let cell = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            // A computation for this key may have finished since the lookup above
+            if let Some(value) = self.lock().peek(&key) {
+                return value.clone();
+            }
+            in_flight.entry(key.clone()).or_default().clone()
+        };
+
+        let mut computed = false;
+        let value = cell
+            .get_or_init(|| {
+                computed = true;
+                f()
+            })
+            .clone();
+
+        if computed {
+            self.lock().set(key.clone(), value.clone());
+            self.in_flight.lock().unwrap().remove(&key);
+        }
+        value
+    }
+
+    /// Returns the number of key-value pairs in the cache.
+    ///
+    /// # Returns
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks to see if the cache is empty.
+    ///
+    /// # Returns
+    /// `true` if the cache holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache<K, V, P>> {
+        self.cache.lock().unwrap()
+    }
+}
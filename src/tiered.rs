@@ -0,0 +1,144 @@
+//!
+//! tiered.rs
+//!
+//! Provides a two-tier cache with a small L1 in front of a larger L2.
+//!
+
+use core::hash::Hash;
+use crate::cache::Cache;
+use crate::error::InsertError;
+use crate::stats::CacheStats;
+
+/// A two-tier cache combining two `Cache` instances.
+///
+/// New entries are written to L1. Entries evicted from L1 are demoted into L2 rather than
+/// dropped, and an L2 hit promotes the entry back into L1. Each tier keeps its own eviction
+/// policy and stats.
+///
+/// # Type Parameters
+/// * `K`: The type of the keys in the cache. Must implement `Eq`, `Hash`, and `Clone`.
+/// * `V`: The type of the values in the cache.
+pub struct TieredCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    l1: Cache<K, V>,
+    l2: Cache<K, V>,
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Creates a new TieredCache from two caches.
+    ///
+    /// # Parameters
+    /// * `l1`: The small, fast tier that is checked first.
+    /// * `l2`: The larger tier that receives entries evicted from L1.
+    ///
+    /// # Returns
+    /// A `TieredCache` instance.
+    pub fn new(l1: Cache<K, V>, l2: Cache<K, V>) -> Self {
+        TieredCache { l1, l2 }
+    }
+
+    /// Inserts a key-value pair into L1, demoting any evicted entries into L2.
+    ///
+    /// If L1 refuses the pair, because it is full of pinned entries or the value is too heavy for
+    /// it, the pair goes into L2 instead.
+    ///
+    /// # Parameters
+    /// * `key`: The key to be inserted or updated.
+    /// * `value`: The value associated with the key.
+    pub fn set(&mut self, key: K, value: V) {
+        // Drop any stale copy from the other tier so a key never lives in both
+        if let Err((key, value)) = self.insert_into_l1(key.clone(), value) {
+            self.l1.remove(&key);
+            self.l2.set(key, value);
+        } else {
+            self.l2.remove(&key);
+        }
+    }
+
+    /// Retrieves a value, checking L1 first and then L2.
+    ///
+    /// A value found in L2 is promoted into L1, unless L1 refuses it, in which case it stays in L2.
+    ///
+    /// # Parameters
+    /// * `key`: The key associated with the value to be returned.
+    ///
+    /// # Returns
+    /// An `Option` containing the value, or `None` if neither tier holds it.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        if self.l1.get(key).is_none() {
+            self.l2.get(key)?;
+            let value = self.l2.remove(key)?;
+            if let Err((_, value)) = self.insert_into_l1(key.clone(), value) {
+                // The slot it was removed from is still free, so putting it back evicts nothing
+                self.l2.set(key.clone(), value);
+                return self.l2.peek(key);
+            }
+        }
+        self.l1.peek(key)
+    }
+
+    /// Removes a key-value pair from whichever tier holds it.
+    ///
+    /// # Parameters
+    /// * `key`: The key to remove.
+    ///
+    /// # Returns
+    /// An `Option` containing the removed value if it exists, or `None` if no value is found.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.l1.remove(key).or_else(|| self.l2.remove(key))
+    }
+
+    /// Returns the number of key-value pairs across both tiers.
+    ///
+    /// # Returns
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.l1.len() + self.l2.len()
+    }
+
+    /// Checks to see if both tiers are empty.
+    ///
+    /// # Returns
+    /// `true` if the cache holds no entries, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.l1.is_empty() && self.l2.is_empty()
+    }
+
+    /// Returns the stats recorded by L1.
+    ///
+    /// # Returns
+    /// The hits, misses and evictions of the first tier.
+    pub fn l1_stats(&self) -> &CacheStats {
+        self.l1.stats()
+    }
+
+    /// Returns the stats recorded by L2.
+    ///
+    /// Only lookups that missed in L1 reach L2, and its evictions are the entries dropped from
+    /// the cache entirely.
+    ///
+    /// # Returns
+    /// The hits, misses and evictions of the second tier.
+    pub fn l2_stats(&self) -> &CacheStats {
+        self.l2.stats()
+    }
+
+    /// Inserts a key-value pair into L1, demoting any evicted entries into L2.
+    ///
+    /// # Returns
+    /// The pair back if L1 refused it.
+    fn insert_into_l1(&mut self, key: K, value: V) -> Result<(), (K, V)> {
+        let demoted = self.l1.try_insert(key, value).map_err(|error| match error {
+            InsertError::Full { key, value } | InsertError::TooLarge { key, value, .. } => (key, value),
+        })?;
+        for (demoted_key, demoted_value) in demoted {
+            self.l2.set(demoted_key, demoted_value);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//!
+//! weigher.rs
+//!
+//! Defines how entries are weighed for caches bounded by total weight, such as bytes.
+//!
+
+/// Measures the weight of a cache entry.
+///
+/// A cache with a weigher evicts entries until the total weight fits its maximum, in addition to
+/// its entry-count capacity. Entries are weighed once when inserted, so changing a value in place
+/// through `values_mut` or `get_or_default` doesn't change its recorded weight.
+///
+/// Closures taking the key and value implement this trait.
+pub trait Weigher<K, V> {
+    /// Weighs an entry.
+    ///
+    /// # Parameters
+    /// * `key`: The entry's key.
+    /// * `value`: The entry's value.
+    ///
+    /// # Returns
+    /// The weight of the entry, in whatever unit the cache's maximum weight uses.
+    fn weigh(&self, key: &K, value: &V) -> usize;
+}
+
+impl<K, V, F> Weigher<K, V> for F
+where
+    F: Fn(&K, &V) -> usize,
+{
+    fn weigh(&self, key: &K, value: &V) -> usize {
+        self(key, value)
+    }
+}
@@ -0,0 +1,110 @@
+//!
+//! analysis_test.rs
+//!
+//! Unit tests for the policy comparison helpers.
+//!
+
+use cache_lib::{ Adaptive, LRU, LFU, FIFO, WTinyLFU };
+use cache_lib::analysis::replay;
+
+/// Tests that replaying an LRU-friendly trace favours LRU over FIFO.
+#[test]
+fn test_replay_lru_beats_fifo() {
+    // Key 1 is reused between every new key, so recency predicts reuse
+    let trace = [1, 2, 1, 3, 1, 4, 1].map(|key: u64| (key, key * 10));
+
+    let lru_stats = replay(Box::new(LRU::new()), 2, &trace);
+    let fifo_stats = replay(Box::new(FIFO::new()), 2, &trace);
+
+    // Check the counters add up to the trace length
+    assert_eq!(lru_stats.hits + lru_stats.misses, trace.len() as u64);
+    assert_eq!(fifo_stats.hits + fifo_stats.misses, trace.len() as u64);
+
+    // Check that LRU keeps key 1 resident while FIFO evicts it
+    assert_eq!(lru_stats.hits, 3);
+    assert_eq!(fifo_stats.hits, 2);
+    assert!(lru_stats.hit_ratio() > fifo_stats.hit_ratio());
+}
+
+/// Tests that an empty trace produces empty stats.
+#[test]
+fn test_replay_empty_trace() {
+    let stats = replay::<u64, u64>(Box::new(LRU::new()), 2, &[]);
+
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, 0);
+    assert_eq!(stats.evictions, 0);
+    assert_eq!(stats.hit_ratio(), 0.0);
+}
+
+/// Tests that W-TinyLFU beats LRU on a skewed trace polluted by one-off keys.
+#[test]
+fn test_replay_wtinylfu_beats_lru() {
+    // A small linear congruential generator keeps the trace reproducible
+    let mut state: u64 = 42;
+    let mut next = move || {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as f64 / (1u64 << 31) as f64
+    };
+
+    // Popular keys are drawn with a Zipf-like skew, interleaved with keys that are never reused
+    let mut trace = Vec::new();
+    for i in 0..20_000u64 {
+        let popular = (next().powi(4) * 500.0) as u64;
+        trace.push((popular, popular));
+        if i % 2 == 0 {
+            trace.push((1_000_000 + i, 0));
+        }
+    }
+
+    let capacity = 50;
+    let lru_stats = replay(Box::new(LRU::new()), capacity, &trace);
+    let tinylfu_stats = replay(Box::new(WTinyLFU::new(capacity, 0.01, 0.8, 256, 4)), capacity, &trace);
+
+    // Check that the admission filter keeps popular keys resident
+    assert!(
+        tinylfu_stats.hit_ratio() > lru_stats.hit_ratio(),
+        "W-TinyLFU hit ratio {} should exceed LRU's {}",
+        tinylfu_stats.hit_ratio(),
+        lru_stats.hit_ratio()
+    );
+}
+
+/// Builds a trace where a warmed-up hot set is reused between one-off keys, which favours LFU.
+fn frequency_trace() -> Vec<(u64, u64)> {
+    let mut trace: Vec<(u64, u64)> = (0..100).map(|i| (i % 10, 0)).collect();
+    for i in 0..3_000u64 {
+        trace.push((i % 10, 0));
+        trace.push((1_000_000 + 2 * i, 0));
+        trace.push((1_000_001 + 2 * i, 0));
+    }
+    trace
+}
+
+/// Builds a trace whose working set moves on every few hundred requests, which favours LRU.
+fn shifting_trace() -> Vec<(u64, u64)> {
+    let mut trace = Vec::new();
+    for phase in 0..30u64 {
+        for i in 0..300 {
+            trace.push((phase * 100 + i % 10, 0));
+        }
+    }
+    trace
+}
+
+/// Tests that the adaptive policy follows whichever of LRU and LFU does better on a trace.
+#[test]
+fn test_replay_adaptive_tracks_winner() {
+    let adaptive = || Box::new(Adaptive::new(Box::new(LRU::new()), Box::new(LFU::new()), 20));
+
+    for (trace, lfu_wins) in [(frequency_trace(), true), (shifting_trace(), false)] {
+        let lru = replay(Box::new(LRU::new()), 12, &trace).hit_ratio();
+        let lfu = replay(Box::new(LFU::new()), 12, &trace).hit_ratio();
+        let adaptive = replay(adaptive(), 12, &trace).hit_ratio();
+        let (winner, loser) = if lfu_wins { (lfu, lru) } else { (lru, lfu) };
+
+        // Check that the adaptive policy lands close to the winner and well clear of the loser
+        assert!(winner > loser);
+        assert!(adaptive > loser + (winner - loser) * 0.8);
+    }
+}
@@ -0,0 +1,108 @@
+//!
+//! array_cache_test.rs
+//!
+//! Tests for the fixed-capacity `ArrayCache`, including that it never allocates.
+//!
+
+use cache_lib::ArrayCache;
+use std::alloc::{ GlobalAlloc, Layout, System };
+use std::cell::Cell;
+
+thread_local! {
+    /// The number of heap allocations made by the current thread.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Wraps the system allocator to count allocations per thread, so parallel tests don't interfere.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f` and returns how many heap allocations it made on this thread.
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Tests that the cache fills up to its capacity and serves what it holds.
+#[test]
+fn test_fill() {
+    let allocations = allocations_during(|| {
+        let mut cache: ArrayCache<u32, u32, 4> = ArrayCache::new();
+        assert!(cache.is_empty());
+        assert_eq!(cache.capacity(), 4);
+
+        for key in 0..4 {
+            assert_eq!(cache.set(key, key * 10), None);
+        }
+        assert_eq!(cache.len(), 4);
+        for key in 0..4 {
+            assert_eq!(cache.get(&key), Some(&(key * 10)));
+        }
+
+        // Check that setting a cached key replaces its value without evicting
+        assert_eq!(cache.set(2, 99), None);
+        assert_eq!(cache.peek(&2), Some(&99));
+        assert_eq!(cache.len(), 4);
+    });
+    assert_eq!(allocations, 0);
+}
+
+/// Tests that a full cache evicts its least recently used entry.
+#[test]
+fn test_eviction() {
+    let allocations = allocations_during(|| {
+        let mut cache: ArrayCache<u32, u32, 4> = ArrayCache::new();
+        for key in 0..4 {
+            cache.set(key, key);
+        }
+
+        // Use 0 so that 1 becomes the least recently used
+        assert_eq!(cache.get(&0), Some(&0));
+        assert_eq!(cache.set(4, 4), Some((1, 1)));
+        assert!(!cache.contains_key(&1));
+
+        // Check that peeking doesn't count as a use, so 2 is evicted next
+        assert_eq!(cache.peek(&2), Some(&2));
+        assert_eq!(cache.set(5, 5), Some((2, 2)));
+        assert_eq!(cache.len(), 4);
+    });
+    assert_eq!(allocations, 0);
+}
+
+/// Tests that removing entries frees their slots for new keys.
+#[test]
+fn test_remove() {
+    let allocations = allocations_during(|| {
+        let mut cache: ArrayCache<u32, u32, 4> = ArrayCache::new();
+        for key in 0..4 {
+            cache.set(key, key);
+        }
+
+        assert_eq!(cache.remove(&1), Some(1));
+        assert_eq!(cache.remove(&1), None);
+        assert_eq!(cache.len(), 3);
+
+        // Check that the freed slot is reused without evicting anything
+        assert_eq!(cache.set(4, 4), None);
+        assert_eq!(cache.len(), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.iter().count(), 0);
+    });
+    assert_eq!(allocations, 0);
+}
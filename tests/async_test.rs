@@ -0,0 +1,45 @@
+//!
+//! async_test.rs
+//!
+//! Unit tests for the asynchronous cache helpers.
+//!
+
+#![cfg(feature = "async")]
+
+use cache_lib::{ Cache, LRU };
+
+/// Tests that the async loader only runs on a miss.
+#[tokio::test]
+async fn test_get_or_insert_with_async() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 2);
+    let mut loads = 0;
+
+    for _ in 0..3 {
+        let value = cache
+            .get_or_insert_with_async(1, || {
+                loads += 1;
+                async {
+                    tokio::task::yield_now().await;
+                    "one".to_string()
+                }
+            })
+            .await;
+        assert_eq!(value, "one");
+    }
+
+    assert_eq!(loads, 1);
+    assert_eq!(cache.stats().misses, 1);
+    assert_eq!(cache.stats().hits, 2);
+}
+
+/// Tests that values loaded asynchronously take part in normal eviction.
+#[tokio::test]
+async fn test_get_or_insert_with_async_evicts() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 1);
+
+    cache.get_or_insert_with_async(1, || async { "one".to_string() }).await;
+    cache.get_or_insert_with_async(2, || async { "two".to_string() }).await;
+
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&"two".to_string()));
+}
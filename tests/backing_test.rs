@@ -0,0 +1,172 @@
+//!
+//! backing_test.rs
+//!
+//! Unit tests for loading from and writing to a backing store.
+//!
+
+use cache_lib::{ Cache, Loader, Writer, WriteMode };
+use std::collections::HashMap;
+use std::sync::{ Arc, Mutex };
+
+/// An in-memory backing store shared between the test and the cache.
+#[derive(Clone, Default)]
+struct FakeStore {
+    entries: Arc<Mutex<HashMap<u64, String>>>,
+    writes: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Loader<u64, String> for FakeStore {
+    fn load(&self, key: &u64) -> Option<String> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+}
+
+impl Writer<u64, String> for FakeStore {
+    fn write(&self, key: &u64, value: &String) {
+        self.writes.lock().unwrap().push(*key);
+        self.entries.lock().unwrap().insert(*key, value.clone());
+    }
+}
+
+/// Tests that misses fall through to the loader and cache its result.
+#[test]
+fn test_loader_populates_misses() {
+    let backing = FakeStore::default();
+    backing.entries.lock().unwrap().insert(1, "one".to_string());
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(2)
+        .loader(Box::new(backing.clone()))
+        .build();
+
+    // Check that the first lookup loads and the second hits the cache
+    assert_eq!(cache.get(&1), Some(&"one".to_string()));
+    assert_eq!(cache.len(), 1);
+    backing.entries.lock().unwrap().clear();
+    assert_eq!(cache.get(&1), Some(&"one".to_string()));
+    assert_eq!(cache.stats().hits, 1);
+    assert_eq!(cache.stats().misses, 1);
+
+    // Check that keys missing from the backing store stay missing
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.len(), 1);
+}
+
+/// Tests that write-through writes every set immediately.
+#[test]
+fn test_write_through() {
+    let backing = FakeStore::default();
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(2)
+        .writer(Box::new(backing.clone()), WriteMode::WriteThrough)
+        .build();
+
+    cache.set(1, "one".to_string());
+    cache.set(1, "uno".to_string());
+
+    assert_eq!(*backing.writes.lock().unwrap(), vec![1, 1]);
+    assert_eq!(backing.entries.lock().unwrap().get(&1), Some(&"uno".to_string()));
+}
+
+/// Tests that write-back only writes entries when they are evicted or flushed.
+#[test]
+fn test_write_back_on_eviction() {
+    let backing = FakeStore::default();
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(1)
+        .loader(Box::new(backing.clone()))
+        .writer(Box::new(backing.clone()), WriteMode::WriteBack)
+        .build();
+
+    // Insert a key-value pair and check that nothing was written yet
+    cache.set(1, "one".to_string());
+    assert!(backing.writes.lock().unwrap().is_empty());
+
+    // Check that evicting the entry writes it back
+    cache.set(2, "two".to_string());
+    assert_eq!(*backing.writes.lock().unwrap(), vec![1]);
+
+    // Check that a loaded entry isn't written back again when evicted
+    assert_eq!(cache.get(&1), Some(&"one".to_string()));
+    assert_eq!(*backing.writes.lock().unwrap(), vec![1, 2]);
+    cache.set(3, "three".to_string());
+    assert_eq!(*backing.writes.lock().unwrap(), vec![1, 2]);
+
+    // Check that flushing writes the remaining entry once
+    cache.flush();
+    cache.flush();
+    assert_eq!(*backing.writes.lock().unwrap(), vec![1, 2, 3]);
+}
+
+/// Tests that get_or_load caches what the loader finds and nothing else.
+#[test]
+fn test_get_or_load_caches_found_values_only() {
+    let backing = FakeStore::default();
+    backing.entries.lock().unwrap().insert(1, "one".to_string());
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(2)
+        .loader(Box::new(backing.clone()))
+        .build();
+
+    // Check that a found value is loaded once and then served from the cache
+    assert_eq!(cache.get_or_load(&1), Some(&"one".to_string()));
+    backing.entries.lock().unwrap().clear();
+    assert_eq!(cache.get_or_load(&1), Some(&"one".to_string()));
+
+    // Check that a key the loader lacks isn't cached, so it is asked again later
+    assert_eq!(cache.get_or_load(&2), None);
+    assert_eq!(cache.len(), 1);
+    backing.entries.lock().unwrap().insert(2, "two".to_string());
+    assert_eq!(cache.get_or_load(&2), Some(&"two".to_string()));
+    assert_eq!(cache.len(), 2);
+}
+
+/// Tests that flushing writes only the entries modified since they were loaded or last flushed.
+#[test]
+fn test_flush_writes_only_dirty_entries() {
+    let backing = FakeStore::default();
+    backing.entries.lock().unwrap().insert(1, "one".to_string());
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(3)
+        .loader(Box::new(backing.clone()))
+        .writer(Box::new(backing.clone()), WriteMode::WriteBack)
+        .build();
+
+    // Load one entry and set two others, so only the set ones are dirty
+    assert_eq!(cache.get(&1), Some(&"one".to_string()));
+    cache.set(2, "two".to_string());
+    cache.set(3, "three".to_string());
+    let mut dirty = cache.dirty_keys();
+    dirty.sort();
+    assert_eq!(dirty, vec![2, 3]);
+
+    // Check that a flush writes the dirty entries and leaves nothing pending
+    cache.flush();
+    let mut writes = backing.writes.lock().unwrap().clone();
+    writes.sort();
+    assert_eq!(writes, vec![2, 3]);
+    assert!(cache.dirty_keys().is_empty());
+
+    // Check that only an entry modified after the flush is written by the next one
+    cache.set(3, "tres".to_string());
+    assert_eq!(cache.dirty_keys(), vec![3]);
+    cache.flush();
+    assert_eq!(backing.writes.lock().unwrap().len(), 3);
+    assert_eq!(backing.entries.lock().unwrap().get(&3), Some(&"tres".to_string()));
+}
+
+/// Tests that evicting a dirty entry writes it before it leaves the cache.
+#[test]
+fn test_eviction_flushes_dirty_entry() {
+    let backing = FakeStore::default();
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(1)
+        .writer(Box::new(backing.clone()), WriteMode::WriteBack)
+        .build();
+    cache.set(1, "one".to_string());
+    assert_eq!(cache.dirty_keys(), vec![1]);
+
+    // Check that the evicted value reached the backing store and the new one is pending
+    cache.set(2, "two".to_string());
+    assert_eq!(backing.entries.lock().unwrap().get(&1), Some(&"one".to_string()));
+    assert_eq!(cache.dirty_keys(), vec![2]);
+}
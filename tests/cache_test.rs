@@ -4,7 +4,11 @@
 //! Unit tests for the caching library.
 //!
 
-use cache_lib::{ Cache, Store, LRU, FIFO, LFU, MRU, RandomEviction, SLRU, SFIFO, KLRU, SecondChance, ARC };
+#![cfg(feature = "std")]
+
+use cache_lib::{ Cache, CacheError, InsertError, GrowOnPressure, Lookup, MockClock, OverflowPolicy, SetOutcome, Store, LRU, FIFO, RingFifo, LFU, LFUDA, DecayingLFU, MRU, RandomEviction, SampledLru, SLRU, SFIFO, KLRU, LruK, SecondChance, ARC, WTinyLFU, MaxIdle, TtlLru, Composite, Optimal, FnPolicy };
+use cache_lib::eviction::EvictionPolicy;
+use std::time::Duration;
 
 /// Custom struct to test the cache with complex types.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -29,15 +33,15 @@ fn test_cache_basic_operations() {
     let value2 = TestValue { data: "value2".to_string() };
 
     // Test insertion and retrieval
-    cache.set(key1.clone(), value1.clone());
+    cache.set(key1, value1.clone());
     assert_eq!(cache.get(&key1), Some(&value1));
 
     // Test updating a value
-    cache.set(key1.clone(), value2.clone());
+    cache.set(key1, value2.clone());
     assert_eq!(cache.get(&key1), Some(&value2));
 
     // Test inserting a second key
-    cache.set(key2.clone(), value1.clone());
+    cache.set(key2, value1.clone());
     assert_eq!(cache.get(&key2), Some(&value1));
 
     // Test removing a key
@@ -59,14 +63,14 @@ fn test_lru_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key1 to make it recently used
     assert_eq!(cache.get(&key1), Some(&value1));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
@@ -85,15 +89,15 @@ fn test_store_operations() {
     let value2 = TestValue { data: "value2".to_string() };
 
     // Test insertion and retrieval
-    store.insert(key1.clone(), value1.clone());
+    store.insert(key1, value1.clone());
     assert_eq!(store.get(&key1), Some(&value1));
 
     // Test updating a value
-    store.insert(key1.clone(), value2.clone());
+    store.insert(key1, value2.clone());
     assert_eq!(store.get(&key1), Some(&value2));
 
     // Test inserting a second key
-    store.insert(key2.clone(), value1.clone());
+    store.insert(key2, value1.clone());
     assert_eq!(store.get(&key2), Some(&value1));
 
     // Test removing a key
@@ -101,8 +105,76 @@ fn test_store_operations() {
     assert_eq!(store.get(&key1), None);
 
     // Test contains_key
-    assert_eq!(store.contains_key(&key2), true);
-    assert_eq!(store.contains_key(&key1), false);
+    assert!(store.contains_key(&key2));
+    assert!(!store.contains_key(&key1));
+}
+
+/// Tests editing a value in place through the Store.
+#[test]
+fn test_store_get_mut() {
+    let mut store = Store::new();
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    store.insert(key1, TestValue { data: "value1".to_string() });
+
+    // Test mutating a value in place
+    if let Some(value) = store.get_mut(&key1) {
+        value.data.push_str("-edited");
+    }
+    assert_eq!(store.get(&key1), Some(&TestValue { data: "value1-edited".to_string() }));
+
+    // Test that a missing key yields nothing
+    assert_eq!(store.get_mut(&key2), None);
+}
+
+/// Tests the Store's len, is_empty and clear.
+#[test]
+fn test_store_len_and_clear() {
+    let mut store = Store::new();
+    assert!(store.is_empty());
+    assert_eq!(store.len(), 0);
+
+    store.insert(TestKey { id: 1 }, TestValue { data: "value1".to_string() });
+    store.insert(TestKey { id: 2 }, TestValue { data: "value2".to_string() });
+    assert!(!store.is_empty());
+    assert_eq!(store.len(), 2);
+
+    // Test that updating a key doesn't change the length
+    store.insert(TestKey { id: 1 }, TestValue { data: "value3".to_string() });
+    assert_eq!(store.len(), 2);
+
+    // Test clearing the store
+    store.clear();
+    assert!(store.is_empty());
+    assert_eq!(store.get(&TestKey { id: 1 }), None);
+}
+
+/// Tests the Cache's len, is_empty and clear.
+#[test]
+fn test_cache_len_and_clear() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+    assert!(cache.is_empty());
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+    assert_eq!(cache.len(), 2);
+
+    // Test clearing the cache
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&key1), None);
+
+    // Test that the cleared cache fills and evicts normally
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+    cache.set(key3, TestValue { data: "value3".to_string() });
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&key1), None);
 }
 
 /// Tests the FIFO eviction policy.
@@ -119,11 +191,11 @@ fn test_fifo_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key1 has been evicted
     assert_eq!(cache.get(&key1), None);
@@ -145,14 +217,14 @@ fn test_lfu_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key1 to increase its frequency
     cache.get(&key1);
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
@@ -174,14 +246,14 @@ fn test_mru_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key2 to make it recently used
     assert_eq!(cache.get(&key2), Some(&value2));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
@@ -203,11 +275,11 @@ fn test_random_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that one of the first two keys has been evicted
     let key1_evicted = cache.get(&key1).is_none();
@@ -230,14 +302,14 @@ fn test_slru_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key1 to move it to the protected segment
     assert_eq!(cache.get(&key1), Some(&value1));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
@@ -263,13 +335,13 @@ fn test_sfifo_eviction_policy() {
     let value5 = TestValue { data: "value5".to_string() };
 
     // Insert four key-value pairs (two per segment)
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
-    cache.set(key3.clone(), value3.clone());
-    cache.set(key4.clone(), value4.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
+    cache.set(key3, value3.clone());
+    cache.set(key4, value4.clone());
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key5.clone(), value5.clone());
+    cache.set(key5, value5.clone());
 
     // Check evictions: one key from each segment should have been evicted
     let evicted_keys = vec![key1, key2, key3, key4].into_iter().filter(|k| cache.get(k).is_none()).collect::<Vec<_>>();
@@ -292,14 +364,14 @@ fn test_klru_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key2 to make it the most recently used
     assert_eq!(cache.get(&key2), Some(&value2));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key1 has been evicted (2nd most recently used)
     assert_eq!(cache.get(&key1), None);
@@ -320,14 +392,14 @@ fn test_second_chance_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key1 to set its reference bit
     assert_eq!(cache.get(&key1), Some(&value1));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
@@ -349,17 +421,2602 @@ fn test_arc_eviction_policy() {
     let value3 = TestValue { data: "value3".to_string() };
 
     // Insert two key-value pairs
-    cache.set(key1.clone(), value1.clone());
-    cache.set(key2.clone(), value2.clone());
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
 
     // Access key1 to move it to t2
     assert_eq!(cache.get(&key1), Some(&value1));
 
     // Insert another key-value pair to trigger eviction
-    cache.set(key3.clone(), value3.clone());
+    cache.set(key3, value3.clone());
+
+    // Check that key2 has been evicted
+    assert_eq!(cache.get(&key2), None);
+    assert_eq!(cache.get(&key1), Some(&value1));
+    assert_eq!(cache.get(&key3), Some(&value3));
+}
+/// Tests that an SLRU whose segments match the cache capacity holds a full cache.
+#[test]
+fn test_slru_fills_to_cache_capacity() {
+    let eviction_policy = Box::new(SLRU::new(1, 2));
+    let mut cache = Cache::new(eviction_policy, 3);
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+    let key4 = TestKey { id: 4 };
+
+    // The probationary segment borrows unused protected room, so nothing is dropped early
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+    cache.set(key3, TestValue { data: "value3".to_string() });
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(key4, TestValue { data: "value4".to_string() });
+
+    // Check that only the least recently used probationary key has been evicted
+    assert_eq!(cache.get(&key1), None);
+    assert!(cache.get(&key2).is_some());
+    assert!(cache.get(&key3).is_some());
+    assert!(cache.get(&key4).is_some());
+}
+
+/// Tests that an SLRU smaller than the cache is rejected.
+#[test]
+#[should_panic(expected = "does not match cache capacity")]
+fn test_slru_smaller_than_cache_capacity() {
+    let eviction_policy = Box::new(SLRU::<TestKey>::new(1, 1));
+    let _cache: Cache<TestKey, TestValue> = Cache::new(eviction_policy, 3);
+}
+
+/// Tests that an SLRU larger than the cache is rejected.
+#[test]
+#[should_panic(expected = "does not match cache capacity")]
+fn test_slru_larger_than_cache_capacity() {
+    let eviction_policy = Box::new(SLRU::<TestKey>::new(2, 2));
+    let _cache: Cache<TestKey, TestValue> = Cache::new(eviction_policy, 3);
+}
+
+/// Tests that a one-shot scan doesn't evict the working set of a scan resistant cache.
+#[test]
+fn test_scan_resistant() {
+    let mut cache: Cache<u64, u64> = Cache::scan_resistant(10);
+
+    // Build a working set that has been read more than once
+    for i in 0..8 {
+        cache.set(i, i);
+        cache.get(&i);
+    }
+
+    // Scan through keys that are read only once
+    for i in 100..200 {
+        cache.set(i, i);
+    }
+
+    // Check that the working set survived the scan
+    for i in 0..8 {
+        assert_eq!(cache.get_shared(&i), Some(&i));
+    }
+}
+
+/// Tests that a scan resistant cache too small for two segments is rejected.
+#[test]
+#[should_panic(expected = "every segment must hold at least one entry")]
+fn test_scan_resistant_too_small() {
+    let _cache: Cache<u64, u64> = Cache::scan_resistant(1);
+}
+
+/// Returns the segment a two-segment SFIFO places a key in, as reported by its debug state.
+fn sfifo_segment(key: &TestKey) -> usize {
+    use cache_lib::PolicyMetadata;
+
+    let mut probe = SFIFO::new(2, 2);
+    probe.on_insert(key);
+    match probe.debug_state()[..] {
+        [(_, PolicyMetadata::Segment(index))] => index,
+        ref state => panic!("unexpected SFIFO state {:?}", state),
+    }
+}
+
+/// Tests that SFIFO keeps every key of a skewed distribution while the policy has room.
+#[test]
+fn test_sfifo_skewed_keys_not_dropped_early() {
+    let mut policy = SFIFO::new(2, 2);
+    assert_eq!(policy.capacity(), 4);
+
+    // Pick three keys that all hash to the same segment
+    let skewed_keys = (0..)
+        .map(|id| TestKey { id })
+        .filter(|key| sfifo_segment(key) == 0)
+        .take(3)
+        .collect::<Vec<_>>();
+
+    for key in &skewed_keys {
+        policy.on_insert(key);
+    }
+
+    // Check that none of the keys were dropped, and they are evicted in insertion order
+    assert_eq!(policy.evict(), Some(skewed_keys[0]));
+    assert_eq!(policy.evict(), Some(skewed_keys[1]));
+    assert_eq!(policy.evict(), Some(skewed_keys[2]));
+    assert_eq!(policy.evict(), None);
+}
+
+/// Tests that an SFIFO whose effective capacity differs from the cache is rejected.
+#[test]
+#[should_panic(expected = "does not match cache capacity")]
+fn test_sfifo_mismatched_cache_capacity() {
+    let eviction_policy = Box::new(SFIFO::<TestKey>::new(2, 2));
+    let _cache: Cache<TestKey, TestValue> = Cache::new(eviction_policy, 3);
+}
+
+/// Tests the Second-Chance eviction policy when every reference bit is set.
+#[test]
+fn test_second_chance_all_referenced() {
+    let mut policy = SecondChance::new();
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+
+    // Insert three keys and access all of them to set every reference bit
+    policy.on_insert(&key1);
+    policy.on_insert(&key2);
+    policy.on_insert(&key3);
+    policy.on_access(&key1);
+    policy.on_access(&key2);
+    policy.on_access(&key3);
+
+    // Check that after one rotation the oldest key is evicted, then the rest in order
+    assert_eq!(policy.evict(), Some(key1));
+    assert_eq!(policy.evict(), Some(key2));
+    assert_eq!(policy.evict(), Some(key3));
+
+    // Check that an empty queue yields nothing
+    assert_eq!(policy.evict(), None);
+}
+
+/// Tests that updating a key under Second-Chance doesn't queue it twice and overfill the cache.
+#[test]
+fn test_second_chance_update_keeps_capacity() {
+    let eviction_policy = Box::new(SecondChance::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    // Update a key, then insert past capacity
+    cache.set(TestKey { id: 1 }, TestValue { data: "value1".to_string() });
+    cache.set(TestKey { id: 1 }, TestValue { data: "value1b".to_string() });
+    cache.set(TestKey { id: 2 }, TestValue { data: "value2".to_string() });
+    cache.set(TestKey { id: 3 }, TestValue { data: "value3".to_string() });
+    cache.set(TestKey { id: 4 }, TestValue { data: "value4".to_string() });
+
+    // Check that the cache never holds more than its capacity and the policy agrees with it
+    assert_eq!(cache.len(), 2);
+    let mut order = cache.eviction_order();
+    order.sort_by_key(|key| key.id);
+    assert_eq!(order, vec![TestKey { id: 3 }, TestKey { id: 4 }]);
+}
+
+/// Replays a trace of keys through a cache, inserting on every miss, and returns the hit count.
+fn count_hits(cache: &mut Cache<TestKey, TestValue>, trace: &[TestKey]) -> usize {
+    let mut hits = 0;
+    for key in trace {
+        if cache.get(key).is_some() {
+            hits += 1;
+        } else {
+            cache.set(*key, TestValue { data: format!("value{}", key.id) });
+        }
+    }
+    hits
+}
+
+/// Tests the Optimal eviction policy against LRU on a cyclic trace.
+#[test]
+fn test_optimal_eviction_policy() {
+    // A cycle one larger than the cache is the worst case for LRU
+    let trace = [1, 2, 3, 1, 2, 3, 1, 2, 3].map(|id| TestKey { id });
+
+    let mut lru_cache = Cache::new(Box::new(LRU::new()), 2);
+    let mut optimal_cache = Cache::new(Box::new(Optimal::new(trace.to_vec())), 2);
+
+    let lru_hits = count_hits(&mut lru_cache, &trace);
+    let optimal_hits = count_hits(&mut optimal_cache, &trace);
+
+    // Check that LRU always misses, while Optimal hits once per cycle after the first
+    assert_eq!(lru_hits, 0);
+    assert_eq!(optimal_hits, 3);
+}
+
+/// Tests that the cache records hits, misses and evictions.
+#[test]
+fn test_cache_stats() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 1);
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    assert!(cache.get(&key1).is_some());
+    assert!(cache.get(&key2).is_none());
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(key2, TestValue { data: "value2".to_string() });
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.hit_ratio(), 0.5);
+}
+
+/// Tests that lookups through a shared reference still count as accesses under LRU.
+#[test]
+fn test_get_shared_records_access() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    let key1 = TestKey { id: 1 };
+    let value1 = TestValue { data: "value1".to_string() };
+    let key2 = TestKey { id: 2 };
+    let value2 = TestValue { data: "value2".to_string() };
+    let key3 = TestKey { id: 3 };
+    let value3 = TestValue { data: "value3".to_string() };
+
+    // Insert two key-value pairs
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
+
+    // Hold two shared lookups at once, accessing key1 last to make it recently used
+    let shared = &cache;
+    let (first, second) = (shared.get_shared(&key2), shared.get_shared(&key1));
+    assert_eq!(first, Some(&value2));
+    assert_eq!(second, Some(&value1));
+    assert_eq!(shared.get_shared(&key3), None);
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(key3, value3.clone());
+
+    // Check that key2 has been evicted and the shared lookups were counted
+    assert_eq!(cache.get(&key2), None);
+    assert_eq!(cache.get(&key1), Some(&value1));
+    assert_eq!(cache.stats().hits, 3);
+    assert_eq!(cache.stats().misses, 2);
+}
+
+/// Tests that every policy's len and contains track inserts, removes and evictions.
+#[test]
+fn test_policy_len_tracks_keys() {
+    let policies: Vec<Box<dyn EvictionPolicy<TestKey>>> = vec![
+        Box::new(LRU::new()),
+        Box::new(FIFO::new()),
+        Box::new(LFU::new()),
+        Box::new(MRU::new()),
+        Box::new(RandomEviction::new()),
+        Box::new(SLRU::new(2, 2)),
+        Box::new(SFIFO::new(2, 2)),
+        Box::new(KLRU::new(0)),
+        Box::new(SecondChance::new()),
+        Box::new(ARC::new(4)),
+        Box::new(WTinyLFU::new(4, 0.25, 0.5, 64, 4)),
+    ];
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+
+    for mut policy in policies {
+        assert!(policy.is_empty());
+
+        policy.on_insert(&key1);
+        policy.on_insert(&key2);
+        policy.on_insert(&key3);
+        assert_eq!(policy.len(), 3);
+        assert!(policy.contains(&key2));
+
+        // Test that removing a key stops it being tracked
+        policy.on_remove(&key2);
+        assert_eq!(policy.len(), 2);
+        assert!(!policy.contains(&key2));
+
+        // Test that evicting a key stops it being tracked
+        let evicted_key = policy.evict().unwrap();
+        assert_eq!(policy.len(), 1);
+        assert!(!policy.contains(&evicted_key));
+    }
+}
+
+/// Tests that updating an existing key in a full cache doesn't evict anything.
+#[test]
+fn test_update_at_capacity_does_not_evict() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+
+    // Update key2 while the cache is full
+    cache.set(key2, TestValue { data: "value3".to_string() });
+
+    // Check that both keys are still present
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&key1), Some(&TestValue { data: "value1".to_string() }));
+    assert_eq!(cache.get(&key2), Some(&TestValue { data: "value3".to_string() }));
+    assert_eq!(cache.stats().evictions, 0);
+}
+
+/// Tests that keys dropped by a policy's own bookkeeping are evicted from the cache.
+#[test]
+fn test_policy_triggered_eviction() {
+    // SLRU(1, 1) used on its own can only track two keys
+    let mut policy = SLRU::new(1, 1);
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+
+    assert_eq!(policy.on_insert(&key1), None);
+    assert_eq!(policy.on_insert(&key2), None);
+
+    // Check that inserting a third key reports the dropped probationary key
+    assert_eq!(policy.on_insert(&key3), Some(key1));
+    assert!(!policy.contains(&key1));
+}
+
+/// Tests that keys SFIFO drops from a full segment are no longer retrievable from the cache.
+#[test]
+fn test_sfifo_dropped_keys_leave_cache() {
+    let policy = SFIFO::new(2, 2);
+
+    // Pick keys that all hash to the same segment
+    let skewed_keys = (0..)
+        .map(|id| TestKey { id })
+        .filter(|key| sfifo_segment(key) == 0)
+        .take(6)
+        .collect::<Vec<_>>();
+
+    let mut cache = Cache::new(Box::new(policy), 4);
+    for key in &skewed_keys {
+        cache.set(*key, TestValue { data: format!("value{}", key.id) });
+    }
+
+    // Check that only the four most recently inserted keys remain
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cache.get(&skewed_keys[0]), None);
+    assert_eq!(cache.get(&skewed_keys[1]), None);
+    for key in &skewed_keys[2..] {
+        assert!(cache.get(key).is_some());
+    }
+}
+
+/// Tests that keys SLRU drops from its probationary segment are no longer retrievable from the cache.
+#[test]
+fn test_slru_dropped_keys_leave_cache() {
+    let eviction_policy = Box::new(SLRU::new(1, 1));
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    let keys = (1..=5).map(|id| TestKey { id }).collect::<Vec<_>>();
+    for key in &keys {
+        cache.set(*key, TestValue { data: format!("value{}", key.id) });
+    }
+
+    // Check that only the two most recently inserted keys remain
+    assert_eq!(cache.len(), 2);
+    for key in &keys[..3] {
+        assert_eq!(cache.get(key), None);
+    }
+    assert!(cache.get(&keys[3]).is_some());
+    assert!(cache.get(&keys[4]).is_some());
+}
+
+/// Tests that updating a key in a full segmented cache keeps every entry.
+#[test]
+fn test_segmented_update_at_capacity() {
+    let mut slru_cache = Cache::new(Box::new(SLRU::new(1, 1)), 2);
+    let mut sfifo_cache = Cache::new(Box::new(SFIFO::new(2, 1)), 2);
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    for cache in [&mut slru_cache, &mut sfifo_cache] {
+        cache.set(key1, TestValue { data: "value1".to_string() });
+        cache.set(key2, TestValue { data: "value2".to_string() });
+
+        // Update a key while the cache is full
+        cache.set(key1, TestValue { data: "value3".to_string() });
+
+        // Check that both keys are still present
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&key1), Some(&TestValue { data: "value3".to_string() }));
+        assert_eq!(cache.get(&key2), Some(&TestValue { data: "value2".to_string() }));
+    }
+}
+
+/// Tests that the builder defaults to an LRU cache with stats enabled.
+#[test]
+fn test_builder_defaults() {
+    let mut cache = Cache::builder().capacity(2).build();
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+
+    // Access key1 to make it recently used
+    assert!(cache.get(&key1).is_some());
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(key3, TestValue { data: "value3".to_string() });
+
+    // Check that key2 has been evicted and the stats were recorded
+    assert_eq!(cache.get(&key2), None);
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+/// Tests that the builder's default TTL expires entries.
+#[test]
+fn test_builder_default_ttl() {
+    let mut cache = Cache::builder()
+        .capacity(2)
+        .default_ttl(Duration::ZERO)
+        .build();
+
+    let key1 = TestKey { id: 1 };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+
+    // Check that the expired entry is treated as a miss and removed
+    assert_eq!(cache.get(&key1), None);
+    assert!(cache.is_empty());
+    assert_eq!(cache.stats().misses, 1);
+}
+
+/// Tests that the builder can disable stats recording.
+#[test]
+fn test_builder_without_stats() {
+    let mut cache = Cache::builder()
+        .capacity(1)
+        .eviction(Box::new(FIFO::new()))
+        .with_stats(false)
+        .build();
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+    cache.set(key2, TestValue { data: "value2".to_string() });
+    assert_eq!(cache.get(&key1), None);
+    assert!(cache.get(&key2).is_some());
+
+    assert_eq!(*cache.stats(), cache_lib::CacheStats::default());
+}
+
+/// Tests a cache that owns its LRU policy without boxing it.
+#[test]
+fn test_static_policy_cache() {
+    let mut cache: Cache<TestKey, TestValue, LRU<TestKey>> = Cache::with_policy(LRU::new(), 2);
+
+    let key1 = TestKey { id: 1 };
+    let value1 = TestValue { data: "value1".to_string() };
+    let key2 = TestKey { id: 2 };
+    let value2 = TestValue { data: "value2".to_string() };
+    let key3 = TestKey { id: 3 };
+    let value3 = TestValue { data: "value3".to_string() };
+
+    // Insert two key-value pairs
+    cache.set(key1, value1.clone());
+    cache.set(key2, value2.clone());
+
+    // Access key1 to make it recently used
+    assert_eq!(cache.get(&key1), Some(&value1));
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(key3, value3.clone());
 
     // Check that key2 has been evicted
     assert_eq!(cache.get(&key2), None);
     assert_eq!(cache.get(&key1), Some(&value1));
     assert_eq!(cache.get(&key3), Some(&value3));
-}
\ No newline at end of file
+}
+
+/// Tests removing a mix of present and absent keys at once.
+#[test]
+fn test_remove_many() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 3);
+
+    let key1 = TestKey { id: 1 };
+    let value1 = TestValue { data: "value1".to_string() };
+    let key2 = TestKey { id: 2 };
+    let key3 = TestKey { id: 3 };
+    let value3 = TestValue { data: "value3".to_string() };
+    let key4 = TestKey { id: 4 };
+    cache.set(key1, value1.clone());
+    cache.set(key3, value3.clone());
+
+    // Check that values come back in key order, with None for absent keys
+    let removed = cache.remove_many(&[key3, key2, key1, key4]);
+    assert_eq!(removed, vec![Some(value3), None, Some(value1), None]);
+    assert!(cache.is_empty());
+
+    // Check that the policy no longer tracks the removed keys
+    for id in 5..=7 {
+        cache.set(TestKey { id }, TestValue { data: format!("value{}", id) });
+    }
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.stats().evictions, 0);
+}
+
+/// Tests that a cached value is returned without calling the factory.
+#[test]
+fn test_try_get_or_insert_with_hit() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    let key1 = TestKey { id: 1 };
+    let value1 = TestValue { data: "value1".to_string() };
+    cache.set(key1, value1.clone());
+
+    let result: Result<&TestValue, String> = cache.try_get_or_insert_with(key1, || panic!("factory called on a hit"));
+    assert_eq!(result, Ok(&value1));
+    assert_eq!(cache.stats().hits, 1);
+}
+
+/// Tests that a successfully computed value is cached with normal eviction.
+#[test]
+fn test_try_get_or_insert_with_success() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 1);
+
+    let key1 = TestKey { id: 1 };
+    let key2 = TestKey { id: 2 };
+    let value2 = TestValue { data: "value2".to_string() };
+    cache.set(key1, TestValue { data: "value1".to_string() });
+
+    let result: Result<&TestValue, String> = cache.try_get_or_insert_with(key2, || Ok(value2.clone()));
+    assert_eq!(result, Ok(&value2));
+
+    // Check that the new value is cached and evicted key1
+    assert_eq!(cache.get(&key2), Some(&value2));
+    assert_eq!(cache.get(&key1), None);
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+/// Tests that a failed computation caches nothing and evicts nothing.
+#[test]
+fn test_try_get_or_insert_with_failure() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 1);
+
+    let key1 = TestKey { id: 1 };
+    let value1 = TestValue { data: "value1".to_string() };
+    let key2 = TestKey { id: 2 };
+    cache.set(key1, value1.clone());
+
+    let result = cache.try_get_or_insert_with(key2, || Err("fetch failed"));
+    assert_eq!(result, Err("fetch failed"));
+
+    // Check that the full cache is untouched
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&key1), Some(&value1));
+    assert_eq!(cache.get(&key2), None);
+    assert_eq!(cache.stats().evictions, 0);
+}
+
+/// Tests that get_or_insert_with only computes values on a miss.
+#[test]
+fn test_get_or_insert_with() {
+    let eviction_policy = Box::new(LRU::new());
+    let mut cache = Cache::new(eviction_policy, 2);
+
+    let key1 = TestKey { id: 1 };
+    let mut calls = 0;
+    for _ in 0..3 {
+        let value = cache.get_or_insert_with(key1, || {
+            calls += 1;
+            TestValue { data: "value1".to_string() }
+        });
+        assert_eq!(value.data, "value1");
+    }
+    assert_eq!(calls, 1);
+}
+
+/// Tests the memory estimate with values of known size.
+#[test]
+fn test_memory_estimate() {
+    use cache_lib::size::SizeOf;
+
+    // Check the sizes reported for common types
+    let value = String::with_capacity(16);
+    assert_eq!(value.size_of(), std::mem::size_of::<String>() + 16);
+    assert_eq!(7u64.size_of(), 8);
+    assert_eq!(vec![1u32, 2, 3].size_of(), std::mem::size_of::<Vec<u32>>() + 12);
+
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 4);
+    let empty = cache.memory_estimate();
+
+    cache.set(1, String::with_capacity(100));
+    let one_entry = cache.memory_estimate();
+
+    cache.set(2, String::with_capacity(200));
+    let two_entries = cache.memory_estimate();
+
+    // Check that each entry adds its key and value sizes on top of the same fixed overhead
+    let first = one_entry - empty;
+    let second = two_entries - one_entry;
+    assert!(first > 8 + std::mem::size_of::<String>() + 100);
+    assert_eq!(second - first, 100);
+
+    // Check that removing an entry gives its memory back
+    cache.remove(&2);
+    assert_eq!(cache.memory_estimate(), one_entry);
+}
+
+/// Tests that memory_estimate_with sizes types that don't implement SizeOf.
+#[test]
+fn test_memory_estimate_with() {
+    /// A value type with no SizeOf impl.
+    struct Opaque([u8; 32]);
+
+    let mut cache: Cache<u64, Opaque> = Cache::new(Box::new(LRU::new()), 4);
+    let empty = cache.memory_estimate_with(std::mem::size_of_val, std::mem::size_of_val);
+
+    // Check that each entry adds the sizes the functions report
+    cache.set(1, Opaque([0; 32]));
+    let inline = cache.memory_estimate_with(std::mem::size_of_val, std::mem::size_of_val);
+    let padded = cache.memory_estimate_with(std::mem::size_of_val, |value| value.0.len() + 100);
+    assert!(inline - empty > 8 + 32);
+    assert_eq!(padded - inline, 100);
+}
+
+/// Tests that priming keeps the insertion order for later evictions and drops overflow.
+#[test]
+fn test_prime() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+
+    // Prime with more entries than the cache can hold
+    cache.prime((1..=5).map(|i| (i, i * 10)));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get_shared(&4), None);
+    assert_eq!(cache.get_shared(&5), None);
+
+    // Check that new inserts evict in primed order
+    cache.set(6, 60);
+    assert_eq!(cache.get_shared(&1), None);
+    cache.set(7, 70);
+    assert_eq!(cache.get_shared(&2), None);
+    assert_eq!(cache.get_shared(&3), Some(&30));
+    assert_eq!(cache.stats().evictions, 2);
+}
+
+/// Tests that invalidating a prefix removes exactly the matching keys.
+#[test]
+fn test_invalidate_prefix() {
+    let mut cache: Cache<&str, u32> = Cache::new(Box::new(LRU::new()), 10);
+
+    // Insert keys under two users
+    for key in ["user:42:profile", "user:42:settings", "user:42:avatar", "user:420:profile", "user:7:profile"] {
+        cache.set(key, 1);
+    }
+
+    assert_eq!(cache.invalidate_prefix("user:42:"), 3);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"user:42:profile"), None);
+    assert_eq!(cache.get(&"user:420:profile"), Some(&1));
+    assert_eq!(cache.get(&"user:7:profile"), Some(&1));
+
+    // Check that a prefix with no matches removes nothing
+    assert_eq!(cache.invalidate_prefix("session:"), 0);
+    assert_eq!(cache.len(), 2);
+}
+
+/// Tests that a formerly hot key becomes evictable once its frequency decays.
+#[test]
+fn test_decaying_lfu() {
+    let clock = MockClock::new();
+    let eviction_policy = DecayingLFU::with_clock(Duration::from_secs(10), Box::new(clock.clone()));
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(eviction_policy), 2);
+
+    // Make key 1 hot, then let its count decay
+    cache.set(1, 1);
+    for _ in 0..10 {
+        cache.get(&1);
+    }
+    clock.advance(Duration::from_secs(60));
+
+    // Make key 2 recently hot
+    cache.set(2, 2);
+    for _ in 0..3 {
+        cache.get(&2);
+    }
+
+    // Check that the decayed key is evicted instead of the recently hot one
+    cache.set(3, 3);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&2));
+    assert_eq!(cache.get(&3), Some(&3));
+}
+
+/// Tests that shrinking a cache returns the evicted entries in eviction order.
+#[test]
+fn test_resize() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 5);
+    for i in 1..=5 {
+        cache.set(i, i * 10);
+    }
+
+    // Shrink the cache and check the least recently used entries are returned
+    let evicted = cache.resize(2);
+    assert_eq!(evicted, vec![(1, 10), (2, 20), (3, 30)]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.stats().evictions, 3);
+    for (key, _) in &evicted {
+        assert_eq!(cache.get(key), None);
+    }
+
+    // Check that growing evicts nothing and the new capacity is used
+    assert!(cache.resize(3).is_empty());
+    cache.set(6, 60);
+    assert_eq!(cache.len(), 3);
+}
+
+/// Tests that consuming a cache yields every inserted pair.
+#[test]
+fn test_into_iter() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 4);
+    for i in 1..=3 {
+        cache.set(i, format!("value{}", i));
+    }
+
+    let mut entries: Vec<(u64, String)> = cache.into_iter().collect();
+    entries.sort();
+    assert_eq!(entries, vec![(1, "value1".to_string()), (2, "value2".to_string()), (3, "value3".to_string())]);
+}
+
+/// Tests that extending a cache inserts every pair and evicts as usual.
+#[test]
+fn test_extend() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 3);
+    cache.set(0, 0);
+
+    // Extend past capacity so later pairs evict earlier ones
+    cache.extend((1..=4).map(|i| (i, i * 10)));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.stats().evictions, 2);
+    assert_eq!(cache.get(&0), None);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&20));
+    assert_eq!(cache.get(&4), Some(&40));
+}
+
+/// Tests that LFU breaks frequency ties by evicting the least recently used key.
+#[test]
+fn test_lfu_tie_breaking() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 4);
+
+    // Insert keys that all share the same frequency
+    for i in 1..=4 {
+        cache.set(i, i);
+    }
+    cache.get(&1);
+    cache.get(&3);
+    cache.get(&2);
+    cache.get(&4);
+
+    // Check that evictions follow the order of last use
+    for (new_key, evicted_key) in [(5, 1), (6, 5), (7, 6)] {
+        cache.set(new_key, new_key);
+        assert_eq!(cache.get_shared(&evicted_key), None);
+    }
+    assert_eq!(cache.get_shared(&3), Some(&3));
+}
+
+/// Tests that LFUDA ages out a formerly hot key that plain LFU would keep forever.
+#[test]
+fn test_lfuda_ages_out_cold_key() {
+    let mut lfu: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 2);
+    let mut lfuda: Cache<u64, u64> = Cache::new(Box::new(LFUDA::new()), 2);
+
+    // Make key 1 hot, then stream keys that are never used again
+    for cache in [&mut lfu, &mut lfuda] {
+        cache.set(1, 1);
+        for _ in 0..5 {
+            cache.get(&1);
+        }
+        for i in 2..=10 {
+            cache.set(i, i);
+        }
+    }
+
+    // Check that LFU keeps the hot key while LFUDA eventually evicts it
+    assert_eq!(lfu.get_shared(&1), Some(&1));
+    assert_eq!(lfuda.get_shared(&1), None);
+    assert_eq!(lfuda.get_shared(&10), Some(&10));
+}
+
+/// Tests that shrinking after a burst keeps the remaining entries and their eviction order.
+#[test]
+fn test_shrink_to_fit() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 1000);
+    for i in 0..1000 {
+        cache.set(i, i);
+    }
+    for i in 3..1000 {
+        cache.remove(&i);
+    }
+    cache.get(&0);
+    cache.shrink_to_fit();
+
+    // Check that the policy still evicts in least recently used order once full again
+    assert_eq!(cache.len(), 3);
+    cache.resize(3);
+    cache.set(3, 3);
+    assert_eq!(cache.get_shared(&1), None);
+
+    // Check that the other surviving entries are still cached
+    assert_eq!(cache.get_shared(&0), Some(&0));
+    assert_eq!(cache.get_shared(&2), Some(&2));
+    assert_eq!(cache.get_shared(&3), Some(&3));
+}
+
+/// Tests that a seeded random eviction policy evicts a predictable key.
+#[test]
+fn test_random_eviction_with_seed() {
+    use rand::{ Rng, SeedableRng };
+    use rand::rngs::StdRng;
+
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(RandomEviction::with_rng(StdRng::seed_from_u64(42))), 4);
+    for i in 1..=4 {
+        cache.set(i, i);
+    }
+    cache.set(5, 5);
+
+    // Check that the victim matches the first draw from the same seed
+    let expected = StdRng::seed_from_u64(42).random_range(0..4) as u64 + 1;
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cache.get(&expected), None);
+    for key in (1..=5).filter(|key| *key != expected) {
+        assert_eq!(cache.get(&key), Some(&key));
+    }
+}
+
+/// Tests that a counter can be incremented through repeated get_or_default calls.
+#[test]
+fn test_get_or_default() {
+    let mut cache: Cache<&str, u32> = Cache::new(Box::new(LRU::new()), 2);
+
+    // Count words, inserting a zero for each new one
+    for word in ["apple", "pear", "apple", "apple"] {
+        *cache.get_or_default(word) += 1;
+    }
+
+    assert_eq!(cache.get(&"apple"), Some(&3));
+    assert_eq!(cache.get(&"pear"), Some(&1));
+    assert_eq!(cache.stats().misses, 2);
+}
+
+/// Tests the bulk contains checks, including the empty-slice edge case.
+#[test]
+fn test_contains_all_and_any() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.set(1, 1);
+    cache.set(2, 2);
+
+    assert!(cache.contains_all(&[1, 2]));
+    assert!(!cache.contains_all(&[1, 3]));
+    assert!(cache.contains_any(&[1, 3]));
+    assert!(!cache.contains_any(&[3, 4]));
+
+    // Check the empty-slice semantics
+    assert!(cache.contains_all(&[]));
+    assert!(!cache.contains_any(&[]));
+
+    // Check that nothing was recorded as an access
+    assert_eq!(cache.stats().hits + cache.stats().misses, 0);
+    cache.set(3, 3);
+    cache.set(4, 4);
+    assert_eq!(cache.get(&1), None);
+}
+
+/// Tests that the remaining time-to-live shrinks as the clock advances.
+#[test]
+fn test_ttl_remaining() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .default_ttl(Duration::from_secs(10))
+        .clock(Box::new(clock.clone()))
+        .build();
+    cache.set(1, 1);
+
+    assert_eq!(cache.ttl_remaining(&1), Some(Duration::from_secs(10)));
+    let expires_at = cache.expires_at(&1).unwrap();
+
+    // Advance the clock and check the remaining time shrinks
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(cache.ttl_remaining(&1), Some(Duration::from_secs(6)));
+    assert_eq!(cache.expires_at(&1), Some(expires_at));
+
+    // Check that expired and absent keys report nothing
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(cache.ttl_remaining(&1), None);
+    assert_eq!(cache.expires_at(&1), None);
+    assert_eq!(cache.ttl_remaining(&2), None);
+}
+
+/// Tests that entries without a time-to-live report no expiry.
+#[test]
+fn test_ttl_remaining_without_ttl() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, 1);
+
+    assert_eq!(cache.ttl_remaining(&1), None);
+    assert_eq!(cache.expires_at(&1), None);
+}
+
+/// Tests that only keys inside the stale window are reported.
+#[test]
+fn test_stale_keys() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .default_ttl(Duration::from_secs(10))
+        .clock(Box::new(clock.clone()))
+        .build();
+
+    // Insert keys at different times so they expire at different times
+    cache.set(1, 1);
+    clock.advance(Duration::from_secs(3));
+    cache.set(2, 2);
+    clock.advance(Duration::from_secs(3));
+    cache.set(3, 3);
+
+    // Key 1 expires in 4s, key 2 in 7s and key 3 in 10s
+    assert_eq!(cache.stale_keys(Duration::from_secs(5)), vec![1]);
+    let mut stale = cache.stale_keys(Duration::from_secs(7));
+    stale.sort();
+    assert_eq!(stale, vec![1, 2]);
+
+    // Check that expired keys are no longer reported
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.stale_keys(Duration::from_secs(3)), vec![2]);
+}
+
+/// Tests that a boxed policy can be downcast back to its concrete type.
+#[test]
+fn test_policy_as_any() {
+    let mut policy: Box<dyn EvictionPolicy<u64>> = Box::new(LRU::new());
+    policy.on_insert(&1);
+
+    // Check that the policy downcasts to its own type only
+    let any = policy.as_any().unwrap();
+    assert!(any.downcast_ref::<FIFO<u64>>().is_none());
+    let lru = any.downcast_ref::<LRU<u64>>().unwrap();
+    assert!(lru.contains(&1));
+}
+
+/// Tests that every policy is empty after a reset and keeps working when refilled.
+#[test]
+fn test_policy_reset() {
+    let policies: Vec<Box<dyn EvictionPolicy<u64>>> = vec![
+        Box::new(LRU::new()),
+        Box::new(FIFO::new()),
+        Box::new(LFU::new()),
+        Box::new(DecayingLFU::new(Duration::from_secs(60))),
+        Box::new(MRU::new()),
+        Box::new(RandomEviction::new()),
+        Box::new(SLRU::new(2, 2)),
+        Box::new(SFIFO::new(2, 2)),
+        Box::new(KLRU::new(0)),
+        Box::new(SecondChance::new()),
+        Box::new(ARC::new(4)),
+        Box::new(WTinyLFU::new(4, 0.25, 0.5, 64, 4)),
+    ];
+
+    for policy in policies {
+        let mut cache: Cache<u64, u64> = Cache::new(policy, 4);
+
+        // Fill and clear the cache, then fill it again past capacity
+        cache.extend((0..6).map(|i| (i, i)));
+        cache.clear();
+        assert!(cache.is_empty());
+        cache.extend((10..16).map(|i| (i, i)));
+
+        // Check that the refilled cache holds only new keys and stays within capacity
+        assert_eq!(cache.len(), 4);
+        assert!((0..6).all(|key| cache.get_shared(&key).is_none()));
+    }
+
+    // Check that a reset policy tracks nothing
+    let mut policy = ARC::new(2);
+    policy.on_insert(&1);
+    policy.on_insert(&2);
+    policy.reset();
+    assert!(policy.is_empty());
+    assert!(!policy.contains(&1));
+}
+
+/// Tests that KLRU falls back to LRU eviction when k is at or above the capacity.
+#[test]
+fn test_klru_large_k() {
+    for k in [3, 10] {
+        let mut cache: Cache<u64, u64> = Cache::new(Box::new(KLRU::new(k)), 3);
+
+        // Insert more keys than the cache can hold
+        for i in 1..=5 {
+            cache.set(i, i);
+            assert!(cache.len() <= 3);
+        }
+
+        // Check that the least recently used keys were evicted
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&5), Some(&5));
+        assert_eq!(cache.stats().evictions, 2);
+    }
+}
+
+/// Tests that values can be updated in bulk without changing the eviction order.
+#[test]
+fn test_keys_and_values_mut() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    for i in 1..=3 {
+        cache.set(i, i);
+    }
+
+    // Double every value
+    for value in cache.values_mut() {
+        *value *= 2;
+    }
+
+    let mut keys: Vec<u64> = cache.keys().copied().collect();
+    keys.sort();
+    assert_eq!(keys, vec![1, 2, 3]);
+
+    // Check that the least recently used key is still evicted first
+    cache.set(4, 8);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&4));
+    assert_eq!(cache.get(&3), Some(&6));
+}
+
+/// Tests that lookups tell hits, negative entries and misses apart.
+#[test]
+fn test_negative_caching() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .capacity(2)
+        .clock(Box::new(clock.clone()))
+        .build();
+
+    cache.set(1, 10);
+    cache.set_negative(2, Duration::from_secs(5));
+
+    assert_eq!(cache.lookup(&1), Lookup::Hit(&10));
+    assert_eq!(cache.lookup(&2), Lookup::Negative);
+    assert_eq!(cache.lookup(&3), Lookup::Miss);
+    assert_eq!(cache.get(&2), None);
+
+    // Check that the negative entry counts toward capacity
+    assert_eq!(cache.len(), 2);
+    cache.set(3, 30);
+    assert_eq!(cache.stats().evictions, 1);
+    assert_eq!(cache.lookup(&1), Lookup::Miss);
+
+    // Check that the negative entry expires
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.lookup(&2), Lookup::Miss);
+
+    // Check that a value replaces a negative entry
+    cache.set_negative(4, Duration::from_secs(5));
+    cache.set(4, 40);
+    assert_eq!(cache.lookup(&4), Lookup::Hit(&40));
+}
+
+/// Tests that LRU-K protects keys with k references where plain LRU would evict them.
+#[test]
+fn test_lru_k_eviction_policy() {
+    let mut lru_k: Cache<u64, u64> = Cache::new(Box::new(LruK::new(2)), 2);
+    let mut lru: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+
+    // Reference key 1 twice and key 2 once, with key 2 the most recent
+    for cache in [&mut lru_k, &mut lru] {
+        cache.set(1, 1);
+        cache.get(&1);
+        cache.set(2, 2);
+        cache.set(3, 3);
+    }
+
+    // Check that LRU-K evicts the key with a single reference and LRU the oldest one
+    assert_eq!(lru_k.get(&1), Some(&1));
+    assert_eq!(lru_k.get(&2), None);
+    assert_eq!(lru.get(&1), None);
+    assert_eq!(lru.get(&2), Some(&2));
+
+    // Check that among keys with k references the oldest k-th reference is evicted
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LruK::new(2)), 2);
+    cache.set(1, 1);
+    cache.set(2, 2);
+    cache.get(&1);
+    cache.get(&2);
+    cache.get(&1);
+    cache.set(3, 3);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&1));
+}
+
+/// Tests that each policy reports its own name.
+#[test]
+fn test_policy_name() {
+    let policies: Vec<(Box<dyn EvictionPolicy<u64>>, &str)> = vec![
+        (Box::new(LRU::new()), "LRU"),
+        (Box::new(FIFO::new()), "FIFO"),
+        (Box::new(LFU::new()), "LFU"),
+        (Box::new(DecayingLFU::new(Duration::from_secs(60))), "DecayingLFU"),
+        (Box::new(MRU::new()), "MRU"),
+        (Box::new(RandomEviction::new()), "RandomEviction"),
+        (Box::new(SLRU::new(2, 2)), "SLRU"),
+        (Box::new(SFIFO::new(2, 2)), "SFIFO"),
+        (Box::new(KLRU::new(1)), "KLRU"),
+        (Box::new(LruK::new(2)), "LruK"),
+        (Box::new(SecondChance::new()), "SecondChance"),
+        (Box::new(ARC::new(4)), "ARC"),
+        (Box::new(Optimal::new(Vec::new())), "Optimal"),
+    ];
+
+    for (policy, name) in policies {
+        let cache: Cache<u64, u64> = Cache::new(policy, 4);
+        assert_eq!(cache.policy_name(), name);
+    }
+}
+
+/// Tests that set_reporting distinguishes inserts, updates and evictions.
+#[test]
+fn test_set_reporting() {
+    let mut cache: Cache<u64, &str> = Cache::new(Box::new(LRU::new()), 2);
+
+    assert_eq!(cache.set_reporting(1, "one"), SetOutcome::Inserted);
+    assert_eq!(cache.set_reporting(2, "two"), SetOutcome::Inserted);
+    assert_eq!(cache.set_reporting(1, "uno"), SetOutcome::Updated);
+
+    // Check that a new key in a full cache reports the displaced entry
+    assert_eq!(cache.set_reporting(3, "three"), SetOutcome::Evicted { key: 2, value: "two" });
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&"uno"));
+}
+
+/// Tests that LFU reports the access count of each key.
+#[test]
+fn test_debug_policy_state() {
+    use cache_lib::PolicyMetadata;
+
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 3);
+    cache.set(1, 1);
+    cache.set(2, 2);
+    cache.set(3, 3);
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&2);
+
+    let mut state = cache.debug_policy_state();
+    state.sort_by_key(|(key, _)| *key);
+    assert_eq!(
+        state,
+        vec![
+            (1, PolicyMetadata::Frequency(3)),
+            (2, PolicyMetadata::Frequency(2)),
+            (3, PolicyMetadata::Frequency(1)),
+        ]
+    );
+}
+
+/// Tests that evictions follow a policy swapped in on a live cache.
+#[test]
+fn test_set_eviction_policy() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 2);
+    cache.set(1, 1);
+    cache.set(2, 2);
+
+    // Switch to MRU and record a fresh access order
+    cache.set_eviction_policy(Box::new(MRU::new()));
+    assert_eq!(cache.policy_name(), "MRU");
+    cache.get(&2);
+    cache.get(&1);
+
+    // Check that the most recently used key is evicted rather than the first inserted one
+    cache.set(3, 3);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&2));
+    assert_eq!(cache.len(), 2);
+}
+
+/// Tests that ARC's ghost lists stay bounded under a long mixed workload.
+#[test]
+fn test_arc_directory_bounded() {
+    let capacity = 4;
+    let mut policy = ARC::new(capacity);
+
+    // Drive the policy the way a cache would, with a skewed mix of repeated and new keys
+    for i in 0..2000u64 {
+        let key = if i % 3 == 0 { i % 7 } else { (i * 7919) % 97 };
+        if policy.contains(&key) {
+            policy.on_access(&key);
+        } else {
+            if policy.len() >= capacity {
+                assert!(policy.evict().is_some());
+            }
+            assert_eq!(policy.on_insert(&key), None);
+        }
+        assert!(policy.len() <= capacity);
+        assert!(policy.directory_len() <= 2 * capacity);
+    }
+}
+
+/// Tests that re-inserting an evicted key counts as a ghost hit and adapts ARC's target.
+#[test]
+fn test_arc_ghost_reinsert() {
+    use cache_lib::PolicyMetadata;
+
+    let mut cache = Cache::with_policy(ARC::new(2), 2);
+    cache.set(1, 1);
+    cache.set(2, 2);
+    cache.get(&2);
+
+    // Evict key 1 into the recency ghost list
+    cache.set(3, 3);
+    assert_eq!(cache.get(&1), None);
+
+    // Re-insert it and check that it skipped t1 and grew the target for t1
+    cache.set(1, 1);
+    let mut state = cache.debug_policy_state();
+    state.sort_by_key(|(key, _)| *key);
+    assert_eq!(state[0], (1, PolicyMetadata::Segment(1)));
+
+    let mut policy = ARC::new(2);
+    policy.on_insert(&1);
+    policy.on_insert(&2);
+    policy.on_access(&2);
+    assert_eq!(policy.evict(), Some(1));
+    assert_eq!(policy.target(), 0);
+    policy.on_insert(&1);
+    assert_eq!(policy.target(), 1);
+    assert!(policy.contains(&1));
+}
+
+/// A policy that silently forgets key 1 whenever key 3 is inserted, like a leaking policy would.
+struct LeakyPolicy {
+    inner: LRU<u64>,
+}
+
+impl EvictionPolicy<u64> for LeakyPolicy {
+    fn on_insert(&mut self, key: &u64) -> Option<u64> {
+        if *key == 3 {
+            self.inner.on_remove(&1);
+        }
+        self.inner.on_insert(key)
+    }
+
+    fn on_access(&mut self, key: &u64) {
+        self.inner.on_access(key)
+    }
+
+    fn on_remove(&mut self, key: &u64) {
+        self.inner.on_remove(key)
+    }
+
+    fn evict(&mut self) -> Option<u64> {
+        self.inner.evict()
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        self.inner.contains(key)
+    }
+}
+
+/// Tests that validate removes entries the policy has lost track of.
+#[test]
+fn test_validate_removes_untracked_entries() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LeakyPolicy { inner: LRU::new() }), 3);
+    cache.set(1, 1);
+    cache.set(2, 2);
+    cache.set(3, 3);
+
+    // Check that only the forgotten key is removed
+    assert_eq!(cache.validate(), 1);
+    assert_eq!(cache.validate(), 0);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.len(), 2);
+
+    // Check that the freed slot is usable and eviction still works normally
+    cache.set(4, 4);
+    cache.set(5, 5);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&2), None);
+}
+
+/// Tests that a cache built from a store holds its entries and tracks them in the policy.
+#[test]
+fn test_from_store() {
+    let mut store = Store::new();
+    for i in 1..=3u64 {
+        store.insert(i, i * 10);
+    }
+
+    let mut cache = Cache::from_store(store, Box::new(LFU::new()), 4);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.debug_policy_state().len(), 3);
+    for i in 1..=3 {
+        assert_eq!(cache.get(&i), Some(&(i * 10)));
+    }
+
+    // Check that a store larger than the capacity is trimmed
+    let mut store = Store::new();
+    for i in 1..=5u64 {
+        store.insert(i, i);
+    }
+    let cache = Cache::from_store(store, Box::new(LRU::new()), 2);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.debug_policy_state().len(), 2);
+}
+
+/// Tests that touching a key changes the next LRU victim.
+#[test]
+fn test_touch() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, 1);
+    cache.set(2, 2);
+
+    assert!(cache.touch(&1));
+    assert!(!cache.touch(&3));
+
+    // Check that the untouched key is evicted instead of the older touched one
+    cache.set(3, 3);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&1));
+    assert_eq!(cache.stats().hits, 1);
+}
+
+/// Tests that the checked cache constructors reject invalid capacities.
+#[test]
+fn test_cache_try_new_rejects_invalid_config() {
+    // Check that a zero capacity is rejected
+    let result: Result<Cache<u64, u64>, _> = Cache::try_new(Box::new(LRU::new()), 0);
+    assert_eq!(result.err(), Some(CacheError::ZeroCapacity));
+
+    // Check that segment sums that differ from the cache capacity are rejected
+    let result: Result<Cache<u64, u64>, _> = Cache::try_new(Box::new(SLRU::new(2, 2)), 3);
+    assert_eq!(result.err(), Some(CacheError::CapacityMismatch { policy_capacity: 4, cache_capacity: 3 }));
+    let result: Result<Cache<u64, u64, _>, _> = Cache::try_with_policy(SFIFO::new(3, 2), 4);
+    assert_eq!(result.err(), Some(CacheError::CapacityMismatch { policy_capacity: 6, cache_capacity: 4 }));
+
+    // Check that a valid configuration is accepted
+    let result: Result<Cache<u64, u64>, _> = Cache::try_new(Box::new(SLRU::new(1, 2)), 3);
+    assert!(result.is_ok());
+}
+
+/// Tests that the checked policy constructors reject invalid parameters.
+#[test]
+fn test_policy_try_new_rejects_invalid_config() {
+    // Check that empty segments are rejected
+    assert_eq!(SLRU::<u64>::try_new(0, 2).err(), Some(CacheError::EmptySegment));
+    assert_eq!(SLRU::<u64>::try_new(2, 0).err(), Some(CacheError::EmptySegment));
+    assert_eq!(SFIFO::<u64>::try_new(0, 2).err(), Some(CacheError::EmptySegment));
+    assert_eq!(SFIFO::<u64>::try_new(2, 0).err(), Some(CacheError::EmptySegment));
+
+    // Check that KLRU's k must leave a key to skip to
+    assert_eq!(KLRU::<u64>::try_new(4, 4).err(), Some(CacheError::KTooLarge { k: 4, capacity: 4 }));
+    assert_eq!(KLRU::<u64>::try_new(0, 0).err(), Some(CacheError::ZeroCapacity));
+    assert!(KLRU::<u64>::try_new(3, 4).is_ok());
+
+    // Check that zero k and zero capacity are rejected
+    assert_eq!(LruK::<u64>::try_new(0).err(), Some(CacheError::ZeroK));
+    assert_eq!(ARC::<u64>::try_new(0).err(), Some(CacheError::ZeroCapacity));
+}
+
+/// Tests that the infallible constructors panic with the error's message.
+#[test]
+#[should_panic(expected = "every segment must hold at least one entry")]
+fn test_sfifo_new_panics_on_empty_segment() {
+    let _policy = SFIFO::<u64>::new(0, 2);
+}
+
+/// Tests that a cache with zero capacity is rejected by the infallible constructor.
+#[test]
+#[should_panic(expected = "capacity must be at least 1")]
+fn test_cache_new_panics_on_zero_capacity() {
+    let _cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 0);
+}
+
+/// Tests that evict_iter drains entries in the policy's eviction order.
+#[test]
+fn test_evict_iter_follows_policy_order() {
+    // Check that FIFO drains in insertion order
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 4);
+    cache.extend([(3, 30), (1, 10), (2, 20)]);
+    cache.get(&3);
+    assert_eq!(cache.evict_iter().collect::<Vec<_>>(), vec![(3, 30), (1, 10), (2, 20)]);
+    assert!(cache.is_empty());
+
+    // Check that LRU drains the least recently used entry first
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 4);
+    cache.extend([(3, 30), (1, 10), (2, 20)]);
+    cache.get(&3);
+    assert_eq!(cache.evict_iter().collect::<Vec<_>>(), vec![(1, 10), (2, 20), (3, 30)]);
+    assert_eq!(cache.stats().evictions, 0);
+
+    // Check that stopping early leaves the remaining entries cached
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 4);
+    cache.extend([(1, 10), (2, 20), (3, 30)]);
+    assert_eq!(cache.evict_iter().next(), Some((1, 10)));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&2), Some(&20));
+}
+
+/// Tests that values cached behind an Arc are shared rather than cloned.
+#[test]
+fn test_get_arc_shares_values() {
+    let mut cache: Cache<&str, std::sync::Arc<[u8]>> = Cache::new(Box::new(LRU::new()), 4);
+    let blob: std::sync::Arc<[u8]> = vec![7u8; 1024].into();
+    cache.set("first", blob.clone());
+    cache.set("second", blob.clone());
+
+    // Check that both keys hand out the same allocation
+    let first = cache.get_arc(&"first").unwrap();
+    let second = cache.get_arc(&"second").unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert!(std::sync::Arc::ptr_eq(&first, &blob));
+
+    // Check that a handle outlives the entry it was read from
+    cache.clear();
+    assert_eq!(first.len(), 1024);
+    assert_eq!(cache.get_arc(&"first"), None);
+}
+
+/// Tests that values of different types stored behind Any are retrieved typed.
+#[test]
+fn test_get_as_downcasts_values() {
+    let mut cache: Cache<&str, Box<dyn std::any::Any>> = Cache::new(Box::new(LRU::new()), 4);
+    cache.set("count", Box::new(42u32));
+    cache.set("name", Box::new("cache".to_string()));
+
+    // Check that each value comes back as its own type
+    assert_eq!(cache.get_as::<u32>(&"count"), Some(&42));
+    assert_eq!(cache.get_as::<String>(&"name"), Some(&"cache".to_string()));
+
+    // Check that asking for the wrong type or a missing key finds nothing
+    assert_eq!(cache.get_as::<String>(&"count"), None);
+    assert_eq!(cache.get_as::<u32>(&"missing"), None);
+}
+
+/// Tests that capacity evictions and TTL expirations are counted separately.
+#[test]
+fn test_eviction_reasons_are_counted_separately() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .capacity(2)
+        .default_ttl(Duration::from_secs(10))
+        .clock(Box::new(clock.clone()))
+        .build();
+
+    // Check that making room counts as a capacity eviction
+    cache.extend([(1, 1), (2, 2), (3, 3)]);
+    assert_eq!(cache.stats().capacity_evictions, 1);
+    assert_eq!(cache.stats().ttl_expirations, 0);
+
+    // Check that a lookup finding an expired entry counts as a TTL expiration
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.stats().capacity_evictions, 1);
+    assert_eq!(cache.stats().ttl_expirations, 1);
+
+    // Check that purging counts the remaining expired entries and keeps live ones
+    cache.set(4, 4);
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.stats().ttl_expirations, 2);
+    assert_eq!(cache.stats().evictions, 3);
+}
+
+/// Tests that removing a key from a multi-segment SFIFO removes it from its own segment.
+#[test]
+fn test_sfifo_remove_targets_assigned_segment() {
+    let mut policy = SFIFO::new(4, 4);
+    for key in 0..12u64 {
+        policy.on_insert(&key);
+    }
+    let segment_of = |policy: &SFIFO<u64>, key: u64| {
+        policy.debug_state().into_iter().find(|(tracked, _)| *tracked == key).map(|(_, segment)| segment)
+    };
+    let before = segment_of(&policy, 5);
+    assert!(before.is_some());
+
+    // Check that only the removed key leaves, and every other key keeps its segment
+    let others: Vec<_> = (0..12u64).filter(|key| *key != 5).map(|key| (key, segment_of(&policy, key))).collect();
+    policy.on_remove(&5);
+    assert!(!policy.contains(&5));
+    assert_eq!(policy.len(), 11);
+    assert_eq!(segment_of(&policy, 5), None);
+    for (key, segment) in others {
+        assert_eq!(segment_of(&policy, key), segment);
+    }
+
+    // Check that re-inserting the key puts it back in the same segment
+    policy.on_insert(&5);
+    assert_eq!(segment_of(&policy, 5), before);
+}
+
+/// Tests that get_many_mut hands out disjoint mutable references and counts each access.
+#[test]
+fn test_get_many_mut() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.extend([(1, 10), (2, 20), (3, 30)]);
+
+    // Check that both values can be changed through one call, in the order requested
+    let values = cache.get_many_mut(&[3, 1]).unwrap();
+    assert_eq!(values.len(), 2);
+    for value in values {
+        *value += 1;
+    }
+    assert_eq!(cache.stats().hits, 2);
+
+    // Check that the accessed keys became most recently used, leaving key 2 to be evicted
+    cache.set(4, 40);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3), Some(&31));
+    assert_eq!(cache.get(&1), Some(&11));
+}
+
+/// Tests that get_many_mut rejects duplicated and missing keys.
+#[test]
+fn test_get_many_mut_rejects_duplicates() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.extend([(1, 10), (2, 20)]);
+
+    // Check that a repeated key would alias, so nothing is returned or recorded
+    assert!(cache.get_many_mut(&[1, 2, 1]).is_none());
+    assert!(cache.get_many_mut(&[1, 5]).is_none());
+    assert_eq!(cache.stats().hits, 0);
+}
+
+/// Tests that MaxIdle drops idle keys on a sweep and prefers them as eviction victims.
+#[test]
+fn test_max_idle_evicts_idle_keys() {
+    let clock = MockClock::new();
+    let policy = MaxIdle::with_clock(LRU::new(), Duration::from_secs(30), Box::new(clock.clone()));
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 10);
+    cache.extend([(1, 10), (2, 20), (3, 30)]);
+
+    // Check that a sweep drops only the key left untouched past the timeout
+    clock.advance(Duration::from_secs(20));
+    cache.get(&1);
+    cache.get(&2);
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(cache.sweep(), vec![(3, 30)]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.stats().ttl_expirations, 1);
+
+    // Check that an idle key is evicted ahead of the inner policy's choice, here FIFO's key 1
+    let policy = MaxIdle::with_clock(FIFO::new(), Duration::from_secs(30), Box::new(clock.clone()));
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 2);
+    cache.extend([(1, 10), (2, 20)]);
+    clock.advance(Duration::from_secs(40));
+    cache.get(&1);
+    cache.set(3, 30);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&10));
+}
+
+/// Tests that TtlLru evicts an expired key ahead of a less recently used live one.
+#[test]
+fn test_ttl_lru_prefers_expired_keys() {
+    let clock = MockClock::new();
+    let mut policy = TtlLru::with_clock(Duration::from_secs(60), Box::new(clock.clone()));
+    policy.on_insert(&1);
+    policy.on_insert(&2);
+    policy.on_insert(&3);
+    assert!(policy.set_ttl(&3, Duration::from_secs(10)));
+    assert!(!policy.set_ttl(&4, Duration::from_secs(10)));
+
+    // Check that the expired key goes first even though key 1 is least recently used
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(policy.evict(), Some(3));
+
+    // Check that LRU order applies once nothing has expired
+    policy.on_access(&1);
+    assert_eq!(policy.evict(), Some(2));
+
+    // Check that a cache sweep drops keys past the default time-to-live
+    let policy = TtlLru::with_clock(Duration::from_secs(60), Box::new(clock.clone()));
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 10);
+    cache.set(1, 10);
+    clock.advance(Duration::from_secs(30));
+    cache.set(2, 20);
+    clock.advance(Duration::from_secs(40));
+    assert_eq!(cache.sweep(), vec![(1, 10)]);
+    assert_eq!(cache.get(&2), Some(&20));
+}
+
+/// Tests that a cloned cache keeps the policy state and evicts exactly like the original.
+#[test]
+fn test_clone_evicts_identically() {
+    let policies: Vec<Box<dyn EvictionPolicy<u64>>> = vec![
+        Box::new(LRU::new()),
+        Box::new(LFU::new()),
+        Box::new(SLRU::new(2, 2)),
+        Box::new(ARC::new(4)),
+        Box::new(WTinyLFU::new(4, 0.25, 0.5, 64, 4)),
+    ];
+
+    for policy in policies {
+        let mut cache: Cache<u64, u64> = Cache::new(policy, 4);
+        cache.extend((1..=4).map(|key| (key, key * 10)));
+        cache.get(&1);
+        cache.get(&1);
+        cache.get(&3);
+
+        // Check that the next insert evicts the same entry from both caches
+        let mut copy = cache.clone();
+        assert_eq!(copy.stats(), cache.stats());
+        assert_eq!(copy.set_reporting(5, 50), cache.set_reporting(5, 50));
+
+        // Check that the caches are independent afterwards
+        copy.remove(&5);
+        assert_eq!(cache.get(&5), Some(&50));
+        assert_eq!(copy.len(), 3);
+    }
+}
+
+/// Tests that cloning a cache whose policy can't be cloned panics with a clear message.
+#[test]
+#[should_panic(expected = "eviction policy does not support cloning")]
+fn test_clone_unsupported_policy_panics() {
+    let cache: Cache<u64, u64> = Cache::new(Box::new(LeakyPolicy { inner: LRU::new() }), 4);
+    let _copy = cache.clone();
+}
+
+/// Tests that a cache's Debug output shows its shape and stays bounded.
+#[test]
+fn test_cache_debug_output() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 4);
+    cache.extend([(1, 10), (2, 20)]);
+
+    // Check that the capacity, length, policy and entries are shown
+    let output = format!("{:?}", cache);
+    assert!(output.contains("capacity: 4"), "{}", output);
+    assert!(output.contains("len: 2"), "{}", output);
+    assert!(output.contains("\"LRU\""), "{}", output);
+    assert!(output.contains("1: Some(10)"), "{}", output);
+
+    // Check that a large cache only lists some of its entries
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 1000);
+    cache.extend((0..1000).map(|key| (key, key)));
+    let output = format!("{:?}", cache);
+    assert!(output.contains("len: 1000"), "{}", output);
+    assert!(output.contains(".."), "{}", output);
+    assert!(output.matches("Some(").count() < 100);
+
+    // Check that policies show their internal ordering
+    let mut policy = FIFO::new();
+    policy.on_insert(&7u64);
+    assert!(format!("{:?}", policy).contains('7'));
+}
+
+/// A policy that tracks keys but never gives one up for eviction.
+struct PinningPolicy {
+    keys: Vec<u64>,
+}
+
+impl EvictionPolicy<u64> for PinningPolicy {
+    fn on_insert(&mut self, key: &u64) -> Option<u64> {
+        self.keys.push(*key);
+        None
+    }
+
+    fn on_access(&mut self, _key: &u64) {}
+
+    fn on_remove(&mut self, key: &u64) {
+        self.keys.retain(|tracked| tracked != key);
+    }
+
+    fn evict(&mut self) -> Option<u64> {
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+/// Builds a full two-entry cache over a policy that never evicts.
+fn pinned_cache(overflow: OverflowPolicy) -> Cache<u64, u64> {
+    let mut cache = Cache::builder()
+        .capacity(2)
+        .eviction(Box::new(PinningPolicy { keys: Vec::new() }))
+        .overflow(overflow)
+        .build();
+    cache.extend([(1, 10), (2, 20)]);
+    cache
+}
+
+/// Tests each overflow policy when the eviction policy has nothing to evict.
+#[test]
+fn test_overflow_policies() {
+    // Check that the default grows past the capacity
+    let mut cache = pinned_cache(OverflowPolicy::default());
+    assert_eq!(cache.set_reporting(3, 30), SetOutcome::Inserted);
+    assert_eq!(cache.len(), 3);
+
+    // Check that rejecting leaves the cache unchanged, while updates still go through
+    let mut cache = pinned_cache(OverflowPolicy::Reject);
+    assert_eq!(cache.set_reporting(3, 30), SetOutcome::Rejected);
+    assert_eq!(cache.get(&3), None);
+    assert_eq!(cache.set_reporting(1, 11), SetOutcome::Updated);
+    assert_eq!(cache.len(), 2);
+
+    // Check that forcing an eviction makes room for the new entry
+    let mut cache = pinned_cache(OverflowPolicy::EvictArbitrary);
+    assert!(matches!(cache.set_reporting(3, 30), SetOutcome::Evicted { .. }));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&3), Some(&30));
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+/// Tests that get_or_insert_with panics, as documented, when a rejecting cache refuses the value.
+#[test]
+#[should_panic(expected = "cache refused the inserted value")]
+fn test_get_or_insert_with_rejected() {
+    let mut cache = pinned_cache(OverflowPolicy::Reject);
+    cache.get_or_insert_with(3, || 30);
+}
+
+/// A key that counts how many times it has been cloned.
+#[derive(Debug)]
+struct CountedKey {
+    id: u64,
+    clones: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl Clone for CountedKey {
+    fn clone(&self) -> Self {
+        self.clones.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        CountedKey { id: self.id, clones: self.clones.clone() }
+    }
+}
+
+impl PartialEq for CountedKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for CountedKey {}
+
+impl std::hash::Hash for CountedKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// A FIFO policy that tracks keys by id, so it never clones them itself.
+struct IdFifo {
+    queue: std::collections::VecDeque<u64>,
+    clones: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl EvictionPolicy<CountedKey> for IdFifo {
+    fn on_insert(&mut self, key: &CountedKey) -> Option<CountedKey> {
+        self.queue.push_back(key.id);
+        None
+    }
+
+    fn on_access(&mut self, _key: &CountedKey) {}
+
+    fn on_remove(&mut self, key: &CountedKey) {
+        self.queue.retain(|&id| id != key.id);
+    }
+
+    fn evict(&mut self) -> Option<CountedKey> {
+        let id = self.queue.pop_front()?;
+        Some(CountedKey { id, clones: self.clones.clone() })
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn contains(&self, key: &CountedKey) -> bool {
+        self.queue.contains(&key.id)
+    }
+}
+
+/// Tests that inserting, including inserts that evict, clones the key at most once.
+#[test]
+fn test_set_clones_key_at_most_once() {
+    let clones = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let policy = IdFifo { queue: std::collections::VecDeque::new(), clones: clones.clone() };
+    let mut cache: Cache<CountedKey, u64> = Cache::new(Box::new(policy), 2);
+
+    for id in 0..10 {
+        let key = CountedKey { id, clones: clones.clone() };
+        let before = clones.load(std::sync::atomic::Ordering::SeqCst);
+        cache.set(key, id);
+
+        // Check that this insert cloned its key no more than once
+        assert!(clones.load(std::sync::atomic::Ordering::SeqCst) - before <= 1);
+    }
+    assert_eq!(cache.len(), 2);
+}
+
+/// An LRU policy that counts how often it is told about removals.
+struct CountingRemoves {
+    inner: LRU<u64>,
+    removes: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl EvictionPolicy<u64> for CountingRemoves {
+    fn on_insert(&mut self, key: &u64) -> Option<u64> {
+        self.inner.on_insert(key)
+    }
+
+    fn on_access(&mut self, key: &u64) {
+        self.inner.on_access(key)
+    }
+
+    fn on_remove(&mut self, key: &u64) {
+        self.removes.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.inner.on_remove(key)
+    }
+
+    fn evict(&mut self) -> Option<u64> {
+        self.inner.evict()
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn contains(&self, key: &u64) -> bool {
+        self.inner.contains(key)
+    }
+}
+
+/// Tests that removing an absent key doesn't reach the eviction policy.
+#[test]
+fn test_remove_absent_key_leaves_policy_untouched() {
+    let removes = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let policy = CountingRemoves { inner: LRU::new(), removes: removes.clone() };
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 2);
+    cache.set(1, 10);
+    cache.set(2, 20);
+
+    // Check that an absent key is reported as such without telling the policy
+    assert_eq!(cache.remove(&3), None);
+    assert_eq!(removes.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    // Check that the policy's order is intact, so key 1 is still evicted first
+    cache.set(3, 30);
+    assert_eq!(cache.get(&1), None);
+
+    // Check that removing a cached key still reaches the policy
+    assert_eq!(cache.remove(&2), Some(20));
+    assert_eq!(removes.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+/// Tests that a bulk insert into a cache that's too small hands back what didn't fit.
+#[test]
+fn test_insert_all_returns_overflow() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 3);
+
+    // Check that the two items pushed out are returned in eviction order
+    let overflow = cache.insert_all((1..=5).map(|key| (key, key * 10)));
+    assert_eq!(overflow, vec![(1, 10), (2, 20)]);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.stats().evictions, 2);
+    for key in 3..=5 {
+        assert_eq!(cache.get(&key), Some(&(key * 10)));
+    }
+}
+
+/// Tests that get_or_insert only stores its default on a miss.
+#[test]
+fn test_get_or_insert() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, "cached".to_string());
+
+    // Check that a hit returns the cached value and leaves it in place
+    assert_eq!(cache.get_or_insert(1, "default".to_string()), "cached");
+    assert_eq!(cache.get(&1), Some(&"cached".to_string()));
+
+    // Check that a miss inserts the default
+    assert_eq!(cache.get_or_insert(2, "default".to_string()), "default");
+    assert_eq!(cache.get(&2), Some(&"default".to_string()));
+    assert_eq!(cache.len(), 2);
+}
+
+/// Tests that LRU reports the keys it tracks.
+#[test]
+fn test_lru_contains_and_len() {
+    let mut policy = LRU::new();
+    assert!(policy.is_empty());
+    policy.on_insert(&1);
+    policy.on_insert(&2);
+
+    // Check that inserted keys are tracked
+    assert_eq!(policy.len(), 2);
+    assert!(policy.contains(&1));
+    assert!(!policy.contains(&3));
+
+    // Check that evicted and removed keys are no longer tracked
+    assert_eq!(policy.evict(), Some(1));
+    policy.on_remove(&2);
+    assert!(!policy.contains(&1));
+    assert!(!policy.contains(&2));
+    assert!(policy.is_empty());
+}
+
+/// Tests that a cloned value is independent of later changes to the cache.
+#[test]
+fn test_get_cloned() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+
+    // Check that the lookup counts as an access, so key 2 is evicted instead of key 1
+    let value = cache.get_cloned(&1);
+    cache.set(3, "three".to_string());
+    assert_eq!(cache.get(&2), None);
+
+    // Check that the copy survives the cached value being replaced and removed
+    cache.set(1, "uno".to_string());
+    cache.remove(&1);
+    assert_eq!(value, Some("one".to_string()));
+    assert_eq!(cache.get_cloned(&1), None);
+}
+
+/// Tests that try_set hands back the evicted entry when it can make room.
+#[test]
+fn test_try_set_evicts() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+    assert_eq!(cache.try_set(1, 10), Ok(None));
+    assert_eq!(cache.try_set(2, 20), Ok(None));
+
+    // Check that a full cache evicts as usual and reports what left
+    assert_eq!(cache.try_set(3, 30), Ok(Some((1, 10))));
+    assert_eq!(cache.try_set(2, 21), Ok(None));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&2), Some(&21));
+}
+
+/// Tests that try_set rejects a pair when nothing can be evicted, even if overflow would grow.
+#[test]
+fn test_try_set_rejects_when_full() {
+    let mut cache = pinned_cache(OverflowPolicy::Grow);
+
+    // Check that the pair comes back and the cache is unchanged
+    assert_eq!(cache.try_set(3, 30), Err(InsertError::Full { key: 3, value: 30 }));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&3), None);
+
+    // Check that updating a cached key needs no room
+    assert_eq!(cache.try_set(1, 11), Ok(None));
+    assert_eq!(cache.get(&1), Some(&11));
+}
+
+/// Tests that hotness ranks keys by how the policy values them.
+#[test]
+fn test_hotness() {
+    // Check that a just-accessed key under LRU scores higher than a stale one
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.extend([(1, 1), (2, 2), (3, 3)]);
+    cache.get(&1);
+    assert_eq!(cache.hotness(&1), Some(1.0));
+    assert_eq!(cache.hotness(&2), Some(0.0));
+    assert!(cache.hotness(&3).unwrap() < cache.hotness(&1).unwrap());
+    assert_eq!(cache.hotness(&4), None);
+
+    // Check that LFU scores relative to the most frequently used key
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 3);
+    cache.extend([(1, 1), (2, 2)]);
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&1);
+    assert_eq!(cache.hotness(&1), Some(1.0));
+    assert_eq!(cache.hotness(&2), Some(0.25));
+
+    // Check that ARC scores keys seen again above keys seen once
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(ARC::new(3)), 3);
+    cache.extend([(1, 1), (2, 2)]);
+    cache.get(&1);
+    assert!(cache.hotness(&1).unwrap() > cache.hotness(&2).unwrap());
+
+    // Check that policies without a score report none
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 3);
+    cache.set(1, 1);
+    assert_eq!(cache.hotness(&1), None);
+}
+
+/// Tests that queue-based policies report their eviction order.
+#[test]
+fn test_eviction_order() {
+    // Check that FIFO reports insertion order, skipping removed keys
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 5);
+    cache.extend((1..=5).map(|key| (key, key)));
+    cache.remove(&2);
+    cache.remove(&4);
+    cache.get(&1);
+    assert_eq!(cache.eviction_order(), vec![1, 3, 5]);
+    cache.set(2, 2);
+    assert_eq!(cache.eviction_order(), vec![1, 3, 5, 2]);
+
+    // Check that SecondChance puts referenced keys after unreferenced ones, as evict would
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(SecondChance::new()), 3);
+    cache.extend((1..=3).map(|key| (key, key)));
+    cache.get(&1);
+    assert_eq!(cache.eviction_order(), vec![2, 3, 1]);
+
+    // Check that policies without a queue report nothing
+    let cache: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 3);
+    assert!(cache.eviction_order().is_empty());
+}
+
+/// Tests that a cache missing too often grows up to its ceiling and no further.
+#[test]
+fn test_grow_on_pressure() {
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .capacity(2)
+        .grow_on_pressure(GrowOnPressure { window: 4, miss_rate: 0.5, factor: 2.0, max_capacity: 10 })
+        .build();
+
+    // Check that a working set the cache keeps up with doesn't make it grow
+    for _ in 0..10 {
+        for key in 0..2 {
+            if cache.get(&key).is_none() {
+                cache.set(key, key);
+            }
+        }
+    }
+    assert_eq!(cache.capacity(), 2);
+
+    // Check that a stream of misses grows the cache up to the ceiling but not beyond
+    for key in 100..200 {
+        if cache.get(&key).is_none() {
+            cache.set(key, key);
+        }
+    }
+    assert_eq!(cache.capacity(), 10);
+    assert_eq!(cache.len(), 10);
+}
+
+/// Tests that LRU evicts several keys in one call, least recently used first.
+#[test]
+fn test_lru_evict_n() {
+    let mut policy = LRU::new();
+    for key in 1..=5 {
+        policy.on_insert(&key);
+    }
+    policy.on_access(&2);
+
+    // Check that the victims come out in eviction order and are no longer tracked
+    assert_eq!(policy.evict_n(3), vec![1, 3, 4]);
+    assert_eq!(policy.len(), 2);
+    assert!(!policy.contains(&1));
+
+    // Check that asking for more keys than are tracked returns what is left
+    assert_eq!(policy.evict_n(5), vec![5, 2]);
+    assert!(policy.is_empty());
+}
+
+/// Tests that reading through a view leaves the LRU order unchanged.
+#[test]
+fn test_view_is_read_only() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.extend([(1, 10), (2, 20), (3, 30)]);
+
+    // Check that the view sees the entries
+    let view = cache.view();
+    assert_eq!(view.len(), 3);
+    assert_eq!(view.peek(&1), Some(&10));
+    assert!(view.contains_key(&2));
+    assert!(!view.contains_key(&4));
+    let mut entries: Vec<(u64, u64)> = view.iter().map(|(&key, &value)| (key, value)).collect();
+    entries.sort();
+    assert_eq!(entries, vec![(1, 10), (2, 20), (3, 30)]);
+
+    // Check that peeking at key 1 didn't save it from eviction
+    cache.set(4, 40);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.stats().hits, 0);
+}
+
+/// Tests that several threads can read through one view at once.
+#[test]
+fn test_view_concurrent_reads() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.extend([(1, 10), (2, 20), (3, 30)]);
+
+    let view = cache.view();
+    std::thread::scope(|scope| {
+        for key in 1..=3 {
+            let view = &view;
+            scope.spawn(move || {
+                assert_eq!(view.peek(&key), Some(&(key * 10)));
+                assert_eq!(view.len(), 3);
+            });
+        }
+    });
+
+    // Check that the reads left the eviction order alone
+    cache.set(4, 40);
+    assert_eq!(cache.get(&1), None);
+}
+
+/// Tests that re-inserting a key FIFO already tracks doesn't queue it twice.
+#[test]
+fn test_fifo_reinsert_queues_key_once() {
+    let mut policy = FIFO::new();
+    policy.on_insert(&1);
+    policy.on_insert(&2);
+    policy.on_insert(&1);
+    assert_eq!(policy.len(), 2);
+
+    // Check that each key is evicted exactly once, in original insertion order
+    assert_eq!(policy.evict(), Some(1));
+    assert_eq!(policy.evict(), Some(2));
+    assert_eq!(policy.evict(), None);
+
+    // Check that updating a cached value doesn't leave a phantom behind for a later eviction
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 2);
+    cache.set(1, 10);
+    cache.set(1, 11);
+    cache.set(2, 20);
+    cache.set(3, 30);
+    assert_eq!(cache.get(&1), None);
+    cache.set(4, 40);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&3), Some(&30));
+    assert_eq!(cache.get(&4), Some(&40));
+}
+
+/// Tests that taking the stats resets them, so the next interval starts from zero.
+#[test]
+fn test_take_stats() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 1);
+    cache.set(1, 10);
+    cache.get(&1);
+    cache.get(&2);
+    cache.set(2, 20);
+
+    // Check that the first interval's events are returned and the counters reset
+    let first = cache.take_stats();
+    assert_eq!((first.hits, first.misses, first.evictions), (1, 1, 1));
+    assert_eq!(cache.stats().hits, 0);
+
+    // Check that the second interval only counts its own events, including queued lookups
+    cache.get_shared(&2);
+    let second = cache.take_stats();
+    assert_eq!((second.hits, second.misses, second.evictions), (1, 0, 0));
+}
+
+/// Tests that eviction passes over pinned entries until they are unpinned.
+#[test]
+fn test_pinned_entries_are_not_evicted() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    assert!(cache.pin(&1));
+    assert!(!cache.pin(&3));
+
+    // Check that the least recently used entry survives because it is pinned
+    cache.set(3, 30);
+    assert_eq!(cache.view().peek(&1), Some(&10));
+    assert_eq!(cache.view().peek(&2), None);
+
+    // Check that the pinned key is still tracked, so it goes once unpinned
+    assert!(cache.unpin(&1));
+    assert!(!cache.is_pinned(&1));
+    cache.set(4, 40);
+    assert_eq!(cache.view().peek(&1), None);
+    assert_eq!(cache.view().peek(&3), Some(&30));
+}
+
+/// Tests that passing over a pinned key keeps what the eviction policy knows about it.
+#[test]
+fn test_pinned_entry_keeps_policy_state() {
+    use cache_lib::PolicyMetadata;
+
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LFU::new()), 2);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&2);
+    cache.get(&2);
+    cache.get(&2);
+    assert!(cache.pin(&1));
+
+    // Check that 1 is the least frequently used, yet 2 goes and 1 keeps its count
+    cache.set(3, 30);
+    assert_eq!(cache.view().peek(&2), None);
+    let count = cache.debug_policy_state().into_iter().find(|&(key, _)| key == 1).map(|(_, metadata)| metadata);
+    assert_eq!(count, Some(PolicyMetadata::Frequency(3)));
+}
+
+/// Tests that a full cache of pinned entries falls back to its overflow policy.
+#[test]
+fn test_all_pinned_uses_overflow_policy() {
+    // Check that the default overflow policy lets the cache grow
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.pin(&1);
+    cache.pin(&2);
+    cache.set(3, 30);
+    assert_eq!(cache.len(), 3);
+
+    // Check that rejecting leaves the pinned entries in place
+    let mut cache: Cache<u64, u64> = Cache::builder().capacity(2).overflow(OverflowPolicy::Reject).build();
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.pin(&1);
+    cache.pin(&2);
+    assert_eq!(cache.set_reporting(3, 30), SetOutcome::Rejected);
+    assert_eq!(cache.len(), 2);
+
+    // Check that resizing stops at the pinned entries and removing a key drops its pin
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.set(3, 30);
+    cache.pin(&1);
+    cache.pin(&2);
+    assert_eq!(cache.resize(1), vec![(3, 30)]);
+    assert_eq!(cache.len(), 2);
+    cache.remove(&1);
+    assert!(!cache.is_pinned(&1));
+}
+
+/// Tests that an entry leaving the cache some other way than `remove` also drops its pin.
+#[test]
+fn test_pin_dropped_with_expired_entry() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .capacity(2)
+        .default_ttl(Duration::from_secs(10))
+        .clock(Box::new(clock.clone()))
+        .build();
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.pin(&1);
+    cache.pin(&2);
+
+    // Check that expiring through a lookup or a purge unpins the key
+    clock.advance(Duration::from_secs(11));
+    assert_eq!(cache.get(&1), None);
+    cache.purge_expired();
+    assert!(!cache.is_pinned(&1));
+    assert!(!cache.is_pinned(&2));
+
+    // Check that setting the key again doesn't bring the old pin back, while updates keep it
+    cache.set(1, 11);
+    assert!(!cache.is_pinned(&1));
+    cache.pin(&1);
+    cache.set(1, 12);
+    assert!(cache.is_pinned(&1));
+}
+
+/// Tests that evict_iter passes over pinned entries and leaves them cached.
+#[test]
+fn test_evict_iter_skips_pinned() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 3);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.set(3, 30);
+    cache.pin(&1);
+
+    assert_eq!(cache.evict_iter().collect::<Vec<_>>(), vec![(2, 20), (3, 30)]);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.is_pinned(&1));
+    assert_eq!(cache.view().peek(&1), Some(&10));
+
+    // Check that the pinned key is still tracked, so it drains once unpinned
+    cache.unpin(&1);
+    assert_eq!(cache.evict_iter().collect::<Vec<_>>(), vec![(1, 10)]);
+}
+
+/// Tests that a closure policy evicts whichever key the closure picks.
+#[test]
+fn test_fn_policy_evicts_chosen_key() {
+    let policy = FnPolicy::new(|keys: &[u64]| keys.iter().min().copied());
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 3);
+    cache.set(5, 50);
+    cache.set(2, 20);
+    cache.set(9, 90);
+
+    // Check that the smallest key goes first, however recently it was used
+    cache.get(&2);
+    cache.set(7, 70);
+    assert_eq!(cache.view().peek(&2), None);
+
+    // Check that removed keys are no longer offered to the closure
+    cache.remove(&5);
+    cache.set(8, 80);
+    cache.set(6, 60);
+    assert_eq!(cache.view().peek(&7), None);
+    assert_eq!(cache.len(), 3);
+}
+
+/// Tests that sampled LRU mostly evicts keys from the older half of the cache.
+#[test]
+fn test_sampled_lru_prefers_old_keys() {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(SampledLru::with_rng(5, StdRng::seed_from_u64(7))), 100);
+    for key in 0..100 {
+        cache.set(key, key);
+    }
+    // Touch the upper half so keys below 50 are the old ones
+    for key in 50..100 {
+        cache.get(&key);
+    }
+
+    // A sample of five misses every old key only about 3% of the time
+    let evicted = cache.resize(80);
+    assert_eq!(evicted.len(), 20);
+    let old = evicted.iter().filter(|&&(key, _)| key < 50).count();
+    assert!(old >= 17, "only {} of 20 victims were old", old);
+}
+
+/// Tests that a scan-mode pass reads every entry without changing the LRU order.
+#[test]
+fn test_scan_mode_leaves_lru_order() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 4);
+    for key in 1..=4 {
+        cache.set(key, key * 10);
+    }
+    cache.get(&1);
+    let recency = |cache: &Cache<u64, u64>| {
+        let mut state = cache.debug_policy_state();
+        state.sort_by_key(|&(key, _)| key);
+        state
+    };
+    let before = recency(&cache);
+
+    // Check that the scan sees every value, including ones only queued by get_shared
+    let total = cache.with_scan_mode(|cache| {
+        let mut total = 0;
+        for key in [4, 3, 2, 1] {
+            total += cache.get(&key).copied().unwrap_or(0);
+        }
+        total + cache.get_shared(&2).copied().unwrap_or(0)
+    });
+    assert_eq!(total, 120);
+    assert_eq!(cache.stats().hits, 6);
+
+    // Check that nothing was promoted and lookups promote again afterwards
+    assert_eq!(recency(&cache), before);
+    cache.get(&2);
+    cache.set(5, 50);
+    assert_eq!(cache.view().peek(&3), None);
+}
+
+/// Tests that the borrowing eviction callback sees each evicted value without taking it.
+#[test]
+fn test_on_evict_borrows_value() {
+    use std::sync::{ Arc, Mutex };
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(2)
+        .on_evict(Box::new(move |key: &u64, value: &String| log.lock().unwrap().push((*key, value.len()))))
+        .build();
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+    cache.set(3, "three".to_string());
+    assert_eq!(*seen.lock().unwrap(), vec![(1, 3)]);
+
+    // Check that the value still reaches the caller of a method returning evicted entries
+    assert_eq!(cache.resize(1), vec![(2, "two".to_string())]);
+    assert_eq!(*seen.lock().unwrap(), vec![(1, 3), (2, 3)]);
+}
+
+/// Tests that the owning eviction callback receives the values the cache would otherwise drop.
+#[test]
+fn test_on_evict_owned_takes_dropped_values() {
+    use std::sync::{ Arc, Mutex };
+
+    let taken = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&taken);
+    let mut cache: Cache<u64, String> = Cache::builder()
+        .capacity(2)
+        .on_evict_owned(Box::new(move |key: u64, value: String| sink.lock().unwrap().push((key, value))))
+        .build();
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+    cache.set(3, "three".to_string());
+    assert_eq!(*taken.lock().unwrap(), vec![(1, "one".to_string())]);
+
+    // Check that entries handed back to the caller bypass the callback
+    assert_eq!(cache.set_reporting(4, "four".to_string()), SetOutcome::Evicted { key: 2, value: "two".to_string() });
+    assert_eq!(cache.resize(1), vec![(3, "three".to_string())]);
+    assert_eq!(taken.lock().unwrap().len(), 1);
+}
+
+/// Tests that a composite of LFU and LRU evicts the least recently used of the least frequent keys.
+#[test]
+fn test_composite_breaks_frequency_ties_by_recency() {
+    let policy = Composite::new(Box::new(LFU::new()), Box::new(LRU::new()));
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(policy), 3);
+    cache.set(1, 10);
+    cache.set(2, 20);
+    cache.set(3, 30);
+
+    // Give every key the same frequency, leaving key 2 the least recently used
+    cache.get(&2);
+    cache.get(&1);
+    cache.get(&3);
+    cache.set(4, 40);
+    assert_eq!(cache.view().peek(&2), None);
+
+    // Check that frequency still comes first: key 4 is the only key used once
+    cache.get(&1);
+    cache.get(&3);
+    cache.set(5, 50);
+    assert_eq!(cache.view().peek(&4), None);
+    assert_eq!(cache.len(), 3);
+}
+
+/// Tests that the ring-buffer FIFO evicts exactly as FIFO does, including after removals.
+#[test]
+fn test_ring_fifo_matches_fifo() {
+    use rand::{ Rng, SeedableRng };
+    use rand::rngs::StdRng;
+
+    let mut fifo: Cache<u64, u64> = Cache::new(Box::new(FIFO::new()), 8);
+    let mut ring: Cache<u64, u64> = Cache::new(Box::new(RingFifo::new(8)), 8);
+    let mut rng = StdRng::seed_from_u64(3);
+    for step in 0..2000 {
+        let key = rng.random_range(0..20);
+        if rng.random_bool(0.25) {
+            assert_eq!(fifo.remove(&key), ring.remove(&key), "remove {} at step {}", key, step);
+        } else {
+            assert_eq!(fifo.set_reporting(key, step), ring.set_reporting(key, step), "set {} at step {}", key, step);
+        }
+        assert_eq!(fifo.eviction_order(), ring.eviction_order(), "order at step {}", step);
+    }
+}
+
+/// Tests that bumping the epoch makes every earlier entry miss, while new entries hit.
+#[test]
+fn test_bump_epoch_invalidates_entries() {
+    let mut cache: Cache<u64, u64> = Cache::new(Box::new(LRU::new()), 4);
+    for key in 1..=3 {
+        cache.set(key, key * 10);
+    }
+    cache.bump_epoch();
+    cache.set(4, 40);
+
+    // Check that old entries miss and are removed lazily, without counting as timed out
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.stats().ttl_expirations, 0);
+    assert_eq!(cache.get(&4), Some(&40));
+
+    // Check that a purge removes the remaining old entry and the key can be stored again
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(cache.len(), 1);
+    cache.set(1, 11);
+    assert_eq!(cache.get(&1), Some(&11));
+}
+
+/// Requires `T: Send` at compile time.
+fn assert_send<T: Send>() {}
+
+/// Tests that a cache with the default boxed policy can move between threads.
+#[test]
+fn test_cache_is_send() {
+    assert_send::<Cache<u64, String>>();
+    assert_send::<Cache<u64, String, RandomEviction<u64>>>();
+
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(RandomEviction::new()), 2);
+    cache.set(1, "one".to_string());
+    let mut cache = std::thread::spawn(move || {
+        cache.set(2, "two".to_string());
+        cache
+    })
+    .join()
+    .unwrap();
+    assert_eq!(cache.get(&2), Some(&"two".to_string()));
+}
+
+/// Requires `T: Sync` at compile time.
+fn assert_sync<T: Sync>() {}
+
+/// Tests that a cache can be read through shared references from several threads at once.
+#[test]
+fn test_cache_is_sync() {
+    assert_sync::<Cache<u64, String>>();
+    assert_sync::<Cache<u64, String, RandomEviction<u64>>>();
+
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+
+    // Check that shared lookups on other threads are applied once the cache is used mutably
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| assert_eq!(cache.get_shared(&1), Some(&"one".to_string())));
+        }
+    });
+    cache.set(3, "three".to_string());
+    assert_eq!(cache.stats().hits, 4);
+    assert_eq!(cache.view().peek(&2), None);
+}
+
+/// Tests that policies only require `Send` keys once they are used as eviction policies.
+#[test]
+fn test_policy_with_non_send_keys() {
+    let policy: LRU<*const u8> = LRU::new();
+    assert!(policy.is_empty());
+    assert!(!policy.contains(&std::ptr::null()));
+}
+
+/// Tests that the age histogram counts entries by time since they were set.
+#[test]
+fn test_age_histogram() {
+    let clock = MockClock::new();
+    let mut cache: Cache<u64, u64> = Cache::builder()
+        .capacity(8)
+        .clock(Box::new(clock.clone()))
+        .build();
+
+    // Store entries so that at the end they are 90s, 30s, 30s, 5s and 0s old
+    cache.set(1, 1);
+    clock.advance(Duration::from_secs(60));
+    cache.set(2, 2);
+    cache.set(3, 3);
+    clock.advance(Duration::from_secs(25));
+    cache.set(4, 4);
+    clock.advance(Duration::from_secs(5));
+    cache.set(5, 5);
+
+    // Check each bucket, including an age exactly on a bound and the overflow bucket
+    let buckets = [Duration::from_secs(5), Duration::from_secs(60)];
+    assert_eq!(cache.age_histogram(&buckets), vec![2, 2, 1]);
+
+    // Check that setting a key again makes it young
+    cache.set(1, 11);
+    assert_eq!(cache.age_histogram(&buckets), vec![3, 2, 0]);
+    assert_eq!(cache.age_histogram(&[]), vec![5]);
+}
+
+/// Tests that get_valid removes a value that fails validation and reports a miss.
+#[test]
+fn test_get_valid_removes_invalid_value() {
+    let mut cache: Cache<u64, String> = Cache::new(Box::new(LRU::new()), 2);
+    cache.set(1, "open".to_string());
+    cache.set(2, "closed".to_string());
+    let is_open = |value: &String| value == "open";
+
+    // Check that a valid value is returned as a hit
+    assert_eq!(cache.get_valid(&1, is_open), Some(&"open".to_string()));
+
+    // Check that an invalid value is dropped from the cache and counted as a miss
+    assert_eq!(cache.get_valid(&2, is_open), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.stats().hits, 1);
+    assert_eq!(cache.stats().misses, 2);
+
+    // Check that the policy no longer tracks the removed key
+    let tracked: Vec<u64> = cache.debug_policy_state().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(tracked, vec![1]);
+}
@@ -0,0 +1,64 @@
+//!
+//! observer_test.rs
+//!
+//! Unit tests for the cache observer hooks.
+//!
+
+use cache_lib::{ Cache, Observer, LRU };
+use std::sync::{ Arc, Mutex };
+
+/// Records every event as a string so tests can assert on the sequence.
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Observer<u64> for Recorder {
+    fn on_hit(&mut self, key: &u64) {
+        self.events.lock().unwrap().push(format!("hit {}", key));
+    }
+
+    fn on_miss(&mut self, key: &u64) {
+        self.events.lock().unwrap().push(format!("miss {}", key));
+    }
+
+    fn on_insert(&mut self, key: &u64) {
+        self.events.lock().unwrap().push(format!("insert {}", key));
+    }
+
+    fn on_evict(&mut self, key: &u64) {
+        self.events.lock().unwrap().push(format!("evict {}", key));
+    }
+}
+
+/// Tests that hits, misses, inserts and evictions are reported in order.
+#[test]
+fn test_observer_receives_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: Cache<u64, &str> = Cache::builder()
+        .capacity(1)
+        .observer(Box::new(Recorder { events: Arc::clone(&events) }))
+        .build();
+
+    cache.set(1, "one");
+    cache.get(&1);
+    cache.set(2, "two");
+    cache.get(&1);
+
+    assert_eq!(*events.lock().unwrap(), vec!["insert 1", "hit 1", "evict 1", "insert 2", "miss 1"]);
+}
+
+/// Tests that deferred lookups are reported once they are applied.
+#[test]
+fn test_observer_receives_shared_lookups() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = Cache::with_policy(LRU::new(), 2);
+    cache.set_observer(Box::new(Recorder { events: Arc::clone(&events) }));
+
+    cache.set(1, "one");
+    cache.get_shared(&1);
+    cache.get_shared(&2);
+    assert_eq!(*events.lock().unwrap(), vec!["insert 1"]);
+
+    cache.set(3, "three");
+    assert_eq!(*events.lock().unwrap(), vec!["insert 1", "hit 1", "miss 2", "insert 3"]);
+}
@@ -0,0 +1,137 @@
+//!
+//! policy_invariants_test.rs
+//!
+//! Property-based tests checking that every eviction policy tracks exactly the keys it was told
+//! about.
+//!
+
+use cache_lib::{ LRU, FIFO, RingFifo, LFU, LFUDA, MRU, SLRU, SFIFO, KLRU, LruK, SecondChance, ARC, WTinyLFU, MaxIdle, TtlLru, Adaptive, Composite, Optimal, FnPolicy };
+#[cfg(feature = "std")]
+use cache_lib::{ DecayingLFU, RandomEviction, SampledLru };
+use cache_lib::eviction::EvictionPolicy;
+use proptest::prelude::*;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// The number of distinct keys the generated operations draw from.
+const KEYS: u8 = 16;
+
+/// The capacity used for policies that don't bound themselves.
+const CAPACITY: usize = 6;
+
+/// A call the cache makes on its eviction policy.
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u8),
+    Access(u8),
+    Remove(u8),
+    Evict,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..KEYS).prop_map(Op::Insert),
+        (0..KEYS).prop_map(Op::Access),
+        (0..KEYS).prop_map(Op::Remove),
+        Just(Op::Evict),
+    ]
+}
+
+/// Builds a fresh instance of every built-in policy, sized for `CAPACITY` keys where needed.
+fn policies() -> Vec<Box<dyn EvictionPolicy<u8>>> {
+    vec![
+        Box::new(LRU::new()),
+        Box::new(FIFO::new()),
+        Box::new(RingFifo::new(CAPACITY)),
+        Box::new(LFU::new()),
+        Box::new(LFUDA::new()),
+        #[cfg(feature = "std")]
+        Box::new(DecayingLFU::new(Duration::from_secs(60))),
+        Box::new(MRU::new()),
+        #[cfg(feature = "std")]
+        Box::new(RandomEviction::new()),
+        #[cfg(feature = "std")]
+        Box::new(SampledLru::new(3)),
+        Box::new(SLRU::new(2, 4)),
+        Box::new(SFIFO::new(2, 3)),
+        Box::new(KLRU::new(2)),
+        Box::new(LruK::new(2)),
+        Box::new(SecondChance::new()),
+        Box::new(ARC::new(CAPACITY)),
+        Box::new(WTinyLFU::new(CAPACITY, 0.25, 0.5, 64, 4)),
+        Box::new(MaxIdle::new(LRU::new(), Duration::from_secs(60))),
+        Box::new(TtlLru::new(Duration::from_secs(60))),
+        Box::new(Adaptive::new(Box::new(LRU::new()), Box::new(LFU::new()), 4)),
+        Box::new(Composite::new(Box::new(LFU::new()), Box::new(LRU::new()))),
+        Box::new(Optimal::new((0..KEYS).cycle().take(64).collect())),
+        Box::new(FnPolicy::new(|keys: &[u8]| keys.iter().max().copied())),
+    ]
+}
+
+/// Removes a key the policy handed back from the model, checking it was being tracked.
+fn forget(policy: &dyn EvictionPolicy<u8>, tracked: &mut HashSet<u8>, key: u8) -> Result<(), TestCaseError> {
+    prop_assert!(tracked.remove(&key), "{} handed back untracked key {}", policy.name(), key);
+    Ok(())
+}
+
+/// Replays the operations the way `Cache` would, checking the policy against a set of the keys
+/// it should be tracking after every step.
+fn check(mut policy: Box<dyn EvictionPolicy<u8>>, ops: &[Op]) -> Result<(), TestCaseError> {
+    let capacity = policy.capacity().unwrap_or(CAPACITY);
+    let mut tracked = HashSet::new();
+    for op in ops {
+        match *op {
+            Op::Insert(key) if !tracked.contains(&key) => {
+                // The cache makes room before inserting into a full policy
+                if tracked.len() >= capacity {
+                    let evicted = policy.evict();
+                    prop_assert!(evicted.is_some(), "{} had nothing to evict while full", policy.name());
+                    forget(policy.as_ref(), &mut tracked, evicted.unwrap())?;
+                }
+                tracked.insert(key);
+                if let Some(dropped) = policy.on_insert(&key) {
+                    forget(policy.as_ref(), &mut tracked, dropped)?;
+                }
+            }
+            // Updating a cached key re-inserts it, which must not track it twice
+            Op::Insert(key) => {
+                if let Some(dropped) = policy.on_insert(&key) {
+                    forget(policy.as_ref(), &mut tracked, dropped)?;
+                }
+            }
+            Op::Access(key) if tracked.contains(&key) => policy.on_access(&key),
+            Op::Remove(key) if tracked.contains(&key) => {
+                policy.on_remove(&key);
+                tracked.remove(&key);
+            }
+            Op::Evict => match policy.evict() {
+                Some(key) => forget(policy.as_ref(), &mut tracked, key)?,
+                None => prop_assert!(tracked.is_empty(), "{} had nothing to evict", policy.name()),
+            },
+            _ => continue,
+        }
+
+        prop_assert_eq!(policy.len(), tracked.len(), "{} len after {:?}", policy.name(), op);
+        for key in 0..KEYS {
+            prop_assert_eq!(
+                policy.contains(&key),
+                tracked.contains(&key),
+                "{} contains({}) after {:?}",
+                policy.name(),
+                key,
+                op
+            );
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    /// Tests that every policy tracks exactly the keys it was told about and only evicts those.
+    #[test]
+    fn policies_track_exactly_their_keys(ops in prop::collection::vec(op(), 0..200)) {
+        for policy in policies() {
+            check(policy, &ops)?;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//!
+//! sketch_test.rs
+//!
+//! Unit tests for the count-min sketch.
+//!
+
+use cache_lib::CountMinSketch;
+
+/// Tests that estimates never undercount and stay close to the true counts.
+#[test]
+fn test_estimates_bound_true_counts() {
+    let mut sketch = CountMinSketch::new(1024, 4);
+    for key in 0..200u64 {
+        for _ in 0..(key % 10) {
+            sketch.increment(&key);
+        }
+    }
+    let total: u64 = (0..200u64).map(|key| key % 10).sum();
+
+    // Check that every estimate lies between the true count and the collision bound
+    let bound = (std::f64::consts::E * total as f64 / 1024.0).ceil() as u64;
+    let mut overestimated = 0;
+    for key in 0..200u64 {
+        let estimate = sketch.estimate(&key);
+        assert!(estimate >= key % 10);
+        if estimate > key % 10 + bound {
+            overestimated += 1;
+        }
+    }
+    assert!(overestimated <= 5, "{} keys exceeded the error bound", overestimated);
+
+    // Check that unseen keys are estimated close to zero
+    assert!(sketch.estimate(&10_000) <= bound);
+}
+
+/// Tests that a heavily counted key stands out from lightly counted ones.
+#[test]
+fn test_heavy_hitter_stands_out() {
+    let mut sketch = CountMinSketch::new(64, 4);
+    for _ in 0..100 {
+        sketch.increment(&"hot");
+    }
+    for key in ["a", "b", "c", "d", "e"] {
+        sketch.increment(&key);
+    }
+
+    // Check that the hot key's estimate dominates
+    assert!(sketch.estimate(&"hot") >= 100);
+    assert!(["a", "b", "c", "d", "e"].iter().all(|key| sketch.estimate(key) < 100));
+}
+
+/// Tests that aging halves counts and reset clears them.
+#[test]
+fn test_age_and_reset() {
+    let mut sketch = CountMinSketch::new(128, 3);
+    for _ in 0..9 {
+        sketch.increment(&1u64);
+    }
+
+    // Check that aging halves the count, rounding down
+    sketch.age();
+    assert_eq!(sketch.estimate(&1), 4);
+
+    // Check that reset forgets every key but keeps the dimensions
+    sketch.reset();
+    assert_eq!(sketch.estimate(&1), 0);
+    assert_eq!(sketch.width(), 128);
+    assert_eq!(sketch.depth(), 3);
+}
+
+/// Tests that a sketch without counters is rejected.
+#[test]
+#[should_panic(expected = "sketch width and depth must be at least 1")]
+fn test_zero_width_panics() {
+    let _sketch = CountMinSketch::<u64>::new(0, 4);
+}
@@ -0,0 +1,68 @@
+//!
+//! snapshot_test.rs
+//!
+//! Unit tests for the snapshot cache.
+//!
+
+#![cfg(feature = "snapshot")]
+
+use cache_lib::{ Cache, SnapshotCache, LRU };
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::sync::Arc;
+use std::thread;
+
+/// Tests that writes are published to readers and evictions follow the wrapped cache.
+#[test]
+fn test_writes_are_published() {
+    let cache = SnapshotCache::new(Cache::with_policy(LRU::new(), 2));
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+
+    // Check that a snapshot taken now isn't affected by later writes
+    let before = cache.snapshot();
+    cache.set(3, "three".to_string());
+    assert_eq!(before.len(), 2);
+
+    // Check that the latest snapshot reflects the eviction and the removal
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.remove(&2).as_deref(), Some(&"two".to_string()));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3).as_deref(), Some(&"three".to_string()));
+    assert_eq!(cache.len(), 1);
+}
+
+/// Tests that many readers see consistent values while a writer updates the cache.
+#[test]
+fn test_concurrent_readers_and_writer() {
+    let cache = Arc::new(SnapshotCache::new(Cache::with_policy(LRU::new(), 100)));
+    let done = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                while !done.load(Ordering::Acquire) {
+                    // Check that every visible value matches its key
+                    for key in 0..100u64 {
+                        if let Some(value) = cache.get(&key) {
+                            assert_eq!(*value, key * 2);
+                        }
+                    }
+                }
+            });
+        }
+
+        scope.spawn(|| {
+            for key in 0..100u64 {
+                cache.set(key, key * 2);
+                if key % 10 == 0 {
+                    thread::yield_now();
+                }
+            }
+            done.store(true, Ordering::Release);
+        });
+    });
+
+    // Check that every write eventually became visible
+    assert_eq!(cache.len(), 100);
+    assert!((0..100u64).all(|key| cache.get(&key).as_deref() == Some(&(key * 2))));
+}
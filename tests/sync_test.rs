@@ -0,0 +1,65 @@
+//!
+//! sync_test.rs
+//!
+//! Unit tests for the thread-safe cache.
+//!
+
+#![cfg(feature = "std")]
+
+use cache_lib::{ Cache, SyncCache, LRU };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::{ Arc, Barrier };
+use std::thread;
+use std::time::Duration;
+
+/// Tests basic operations through a shared SyncCache.
+#[test]
+fn test_sync_cache_basic_operations() {
+    let cache = SyncCache::new(Cache::with_policy(LRU::new(), 2));
+
+    cache.set(1, "one".to_string());
+    cache.set(2, "two".to_string());
+    assert_eq!(cache.get(&1), Some("one".to_string()));
+
+    // Insert another key-value pair to trigger eviction
+    cache.set(3, "three".to_string());
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.len(), 2);
+
+    assert_eq!(cache.remove(&1), Some("one".to_string()));
+    assert_eq!(cache.get(&1), None);
+}
+
+/// Tests that concurrent misses on the same key run the factory exactly once.
+#[test]
+fn test_single_flight_get_or_insert_with() {
+    const THREADS: usize = 8;
+
+    let cache = Arc::new(SyncCache::new(Cache::with_policy(LRU::new(), 4)));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(THREADS));
+
+    let handles = (0..THREADS)
+        .map(|_| {
+            let cache = Arc::clone(&cache);
+            let calls = Arc::clone(&calls);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                cache.get_or_insert_with(1u64, || {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(50));
+                    "one".to_string()
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "one");
+    }
+
+    // Check that the value was computed once and cached
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.get(&1), Some("one".to_string()));
+}
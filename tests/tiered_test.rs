@@ -0,0 +1,77 @@
+//!
+//! tiered_test.rs
+//!
+//! Unit tests for the two-tier cache.
+//!
+
+use cache_lib::{ Cache, TieredCache, LRU };
+
+fn tiered(l1_capacity: usize, l2_capacity: usize) -> TieredCache<u64, &'static str> {
+    TieredCache::new(
+        Cache::new(Box::new(LRU::new()), l1_capacity),
+        Cache::new(Box::new(LRU::new()), l2_capacity),
+    )
+}
+
+/// Tests that an entry evicted from L1 lands in L2 and is still retrievable.
+#[test]
+fn test_l1_eviction_demotes_to_l2() {
+    let mut cache = tiered(1, 2);
+
+    // Insert two key-value pairs, pushing the first out of L1
+    cache.set(1, "one");
+    cache.set(2, "two");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.l1_stats().evictions, 1);
+
+    // Check that the demoted entry is found in L2 and promoted back
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.l1_stats().misses, 1);
+    assert_eq!(cache.l2_stats().hits, 1);
+
+    // Check that the promotion demoted the other entry
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.l1_stats().hits, 1);
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.l2_stats().hits, 2);
+}
+
+/// Tests that entries evicted from both tiers are gone.
+#[test]
+fn test_l2_eviction_drops_entry() {
+    let mut cache = tiered(1, 1);
+
+    cache.set(1, "one");
+    cache.set(2, "two");
+    cache.set(3, "three");
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.l2_stats().evictions, 1);
+    assert_eq!(cache.remove(&2), Some("two"));
+    assert_eq!(cache.remove(&3), Some("three"));
+    assert!(cache.is_empty());
+}
+
+/// Tests that a value L1 refuses is kept in L2 instead of being lost.
+#[test]
+fn test_value_refused_by_l1_stays_in_l2() {
+    let l1 = Cache::builder()
+        .capacity(2)
+        .weigher(Box::new(|_: &u64, value: &&'static str| value.len()), 3)
+        .build();
+    let mut cache = TieredCache::new(l1, Cache::new(Box::new(LRU::new()), 2));
+
+    // Check that a value too heavy for L1 is still found, and stays put when looked up
+    cache.set(1, "eleven");
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&1), Some(&"eleven"));
+    assert_eq!(cache.get(&1), Some(&"eleven"));
+    assert_eq!(cache.l2_stats().hits, 2);
+
+    // Check that a refused update doesn't leave the old value behind in L1
+    cache.set(2, "two");
+    cache.set(2, "twelve");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&2), Some(&"twelve"));
+}
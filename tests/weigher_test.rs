@@ -0,0 +1,149 @@
+//!
+//! weigher_test.rs
+//!
+//! Unit tests for weight-bounded caches.
+//!
+
+use cache_lib::{ Cache, InsertError, SetOutcome };
+
+/// Builds a cache of strings weighed by their length in bytes.
+fn byte_cache(max_weight: usize) -> Cache<u64, String> {
+    Cache::builder()
+        .capacity(100)
+        .weigher(Box::new(|_: &u64, value: &String| value.len()), max_weight)
+        .build()
+}
+
+/// Tests that the total weight follows inserts and removals.
+#[test]
+fn test_weight_tracks_entries() {
+    let mut cache = byte_cache(10);
+    cache.set(1, "abc".to_string());
+    cache.set(2, "de".to_string());
+
+    // Check that each insert adds its weight
+    assert_eq!(cache.weight(), 5);
+
+    // Check that removing an entry subtracts its weight
+    cache.remove(&1);
+    assert_eq!(cache.weight(), 2);
+
+    // Check that clearing the cache resets the weight
+    cache.clear();
+    assert_eq!(cache.weight(), 0);
+}
+
+/// Tests that replacing a value applies only the difference in weight.
+#[test]
+fn test_replacement_weight_delta() {
+    let mut cache = byte_cache(10);
+    cache.set(1, "abc".to_string());
+    cache.set(2, "de".to_string());
+
+    // Check that a heavier replacement adds the difference
+    assert_eq!(cache.set_reporting(1, "abcdef".to_string()), SetOutcome::Updated);
+    assert_eq!(cache.weight(), 8);
+
+    // Check that a lighter replacement subtracts the difference
+    cache.set(1, "a".to_string());
+    assert_eq!(cache.weight(), 3);
+
+    // Check that a replacement that fits only because the old value leaves evicts nothing
+    cache.set(1, "abcdefgh".to_string());
+    assert_eq!(cache.weight(), 10);
+    assert_eq!(cache.len(), 2);
+}
+
+/// Tests that inserts evict as many entries as needed to stay within the maximum weight.
+#[test]
+fn test_evicts_until_within_budget() {
+    let mut cache = byte_cache(10);
+    cache.set(1, "abcd".to_string());
+    cache.set(2, "efg".to_string());
+    cache.set(3, "hi".to_string());
+
+    // Check that a heavy value evicts the least recently used entries until it fits
+    assert_eq!(
+        cache.set_reporting(4, "jklmnop".to_string()),
+        SetOutcome::Evicted { key: 1, value: "abcd".to_string() }
+    );
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3), Some(&"hi".to_string()));
+    assert_eq!(cache.weight(), 9);
+    assert_eq!(cache.stats().evictions, 2);
+}
+
+/// Tests that a value heavier than the maximum weight is rejected.
+#[test]
+fn test_rejects_oversized_value() {
+    let mut cache = byte_cache(4);
+    cache.set(1, "ab".to_string());
+
+    // Check that an oversized new value is not cached and evicts nothing
+    assert_eq!(cache.set_reporting(2, "abcde".to_string()), SetOutcome::Rejected);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some(&"ab".to_string()));
+    assert_eq!(cache.weight(), 2);
+
+    // Check that an oversized update drops the old value
+    assert_eq!(cache.set_reporting(1, "abcde".to_string()), SetOutcome::Rejected);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.weight(), 0);
+    assert!(cache.is_empty());
+}
+
+/// Tests that priming stops once the next entry would exceed the maximum weight.
+#[test]
+fn test_prime_respects_weight() {
+    let mut cache = byte_cache(5);
+    cache.prime(vec![(1, "ab".to_string()), (2, "cd".to_string()), (3, "ef".to_string())]);
+
+    // Check that only the entries that fit were loaded
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.weight(), 4);
+    assert_eq!(cache.get(&3), None);
+}
+
+/// Tests that try_set reports an oversized value without evicting anything.
+#[test]
+fn test_try_set_rejects_oversized_value() {
+    let mut cache = byte_cache(6);
+    cache.set(1, "abc".to_string());
+    cache.set(2, "de".to_string());
+
+    // Check that the error carries the weights and hands the pair back
+    assert_eq!(
+        cache.try_set(3, "fghijkl".to_string()),
+        Err(InsertError::TooLarge { key: 3, value: "fghijkl".to_string(), weight: 7, max: 6 })
+    );
+
+    // Check that the existing entries survive, including when the oversized value is an update
+    assert_eq!(
+        cache.try_set(1, "fghijkl".to_string()),
+        Err(InsertError::TooLarge { key: 1, value: "fghijkl".to_string(), weight: 7, max: 6 })
+    );
+    assert_eq!(cache.get(&1), Some(&"abc".to_string()));
+    assert_eq!(cache.get(&2), Some(&"de".to_string()));
+    assert_eq!(cache.weight(), 5);
+    assert_eq!(cache.stats().evictions, 0);
+}
+
+/// Tests that a clone of a weight-bounded cache keeps the weight bound and evicts identically.
+#[test]
+fn test_clone_keeps_weight_bound() {
+    let mut cache = byte_cache(10);
+    cache.set(1, "abcd".to_string());
+    cache.set(2, "efgh".to_string());
+    let mut copy = cache.clone();
+
+    // Check that both caches make room the same way when the next value would exceed the bound
+    for cache in [&mut cache, &mut copy] {
+        cache.set(3, "ijkl".to_string());
+        assert_eq!(cache.weight(), 8);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&3), Some(&"ijkl".to_string()));
+    }
+
+    // Check that the copy still turns away values heavier than the bound
+    assert!(matches!(copy.try_set(4, "x".repeat(11)), Err(InsertError::TooLarge { .. })));
+}